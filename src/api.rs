@@ -0,0 +1,82 @@
+// src/api.rs
+
+//! Fachada para usar axes como biblioteca (ej. desde una extensión de editor) sin lanzar
+//! el binario. Cubre lo mismo que `axes <contexto> run <script>`: resolver un contexto a su
+//! configuración y ejecutar un script con sus dependencias.
+//!
+//! Resolver un contexto puede pedir una selección al usuario (ej. `*` sin caché de último
+//! hijo). Quien no tenga terminal debería llamar antes a `prompt::configure(true, None)`
+//! para que esas preguntas fallen en lugar de bloquearse.
+
+use crate::core::config_resolver::{self, ResolverError};
+use crate::core::context_resolver::{self, ContextError};
+use crate::core::index_manager::{self, IndexError};
+use crate::core::runner::{self, RunError, RunReport};
+use crate::models::ResolvedConfig;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Index(#[from] IndexError),
+    #[error(transparent)]
+    Context(#[from] ContextError),
+    #[error(transparent)]
+    Config(#[from] ResolverError),
+    #[error(transparent)]
+    Run(#[from] RunError),
+}
+
+impl Error {
+    /// Código de salida del comando que falló, si el error viene de ejecutar un script.
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            Error::Run(e) => e.exit_code(),
+            _ => None,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Resuelve un contexto (`app/api`, `alias!`, `.`, `**`...) a la configuración fusionada
+/// de su proyecto. `.` y `_` se resuelven desde el directorio actual del proceso.
+pub fn resolve(context: &str) -> Result<ResolvedConfig> {
+    resolve_in(context, None)
+}
+
+/// Como `resolve`, pero `.` y `_` se resuelven desde `base_dir`.
+pub fn resolve_from(context: &str, base_dir: &Path) -> Result<ResolvedConfig> {
+    resolve_in(context, Some(base_dir))
+}
+
+fn resolve_in(context: &str, base_dir: Option<&Path>) -> Result<ResolvedConfig> {
+    let index = index_manager::load_and_ensure_global_project()?;
+    let (uuid, qualified_name) = context_resolver::resolve_context_from(context, &index, base_dir)?;
+    Ok(config_resolver::resolve_config_for_uuid(
+        uuid,
+        qualified_name,
+        &index,
+    )?)
+}
+
+/// Ejecuta el script `name` del proyecto, precedido de sus `depends_on` y con sus hooks
+/// `pre`/`post`. `params` se pasan solo a `name`. No pide la confirmación `confirm`.
+/// Devuelve el informe de los comandos ejecutados (comando, código de salida, duración).
+pub fn run_script(config: &ResolvedConfig, name: &str, params: &[String]) -> Result<RunReport> {
+    let mut report = RunReport::default();
+    run_script_into(config, name, params, &mut report)?;
+    Ok(report)
+}
+
+/// Como `run_script`, pero anota los comandos en `report`, que conserva los pasos ya
+/// ejecutados aunque la ejecución falle.
+pub fn run_script_into(
+    config: &ResolvedConfig,
+    name: &str,
+    params: &[String],
+    report: &mut RunReport,
+) -> Result<()> {
+    Ok(runner::run_with_dependencies(config, name, params, report)?)
+}
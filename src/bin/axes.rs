@@ -4,25 +4,85 @@ use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
 use clap::Parser;
-use std::{env, fs, path::PathBuf};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 use uuid::Uuid;
 
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufRead, BufReader, IsTerminal, Read};
+use std::process::Stdio;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
 
 use axes::cli::Cli;
 use axes::models::Runnable;
+use axes::output::{self, OutputLevel};
+use axes::prompt;
+use axes::status;
+use axes::system::executor::ExecutionError;
 use axes::system::shell;
+use axes::system::watcher::WatchOptions;
 
-use axes::constants::{AXES_DIR, PROJECT_CONFIG_FILENAME};
+use axes::constants::{
+    AXES_DIR, CHILDREN_CACHE_FILENAME, CONFIG_CACHE_FILENAME, LAST_USED_CACHE_FILENAME,
+    PROJECT_CONFIG_FILENAME, PROJECT_REF_FILENAME,
+};
 use axes::core::graph_display;
+use axes::core::index_manager::{IndexError, IndexIssue};
+use axes::core::runner::{
+    self, RunError, RunReport, get_script_runnable, is_command_available, resolve_script_order,
+    select_platform_runnable,
+};
 use axes::core::{
-    config_resolver, context_resolver, index_manager, onboarding_manager,
-    onboarding_manager::OnboardingOptions,
+    config_editor::ConfigDocument, config_resolver, context_resolver, index_manager,
+    onboarding_manager, onboarding_manager::OnboardingOptions, templates,
+};
+use axes::models::{
+    Command as ProjectCommand, GlobalIndex, ProjectConfig, ProjectRef, ResolvedConfig,
 };
-use axes::models::{Command as ProjectCommand, ProjectConfig, ProjectRef, ResolvedConfig};
 
-use dialoguer::{Confirm, theme::ColorfulTheme};
+use dialoguer::{Input, Select};
+
+/// Acciones de sistema que operan sobre un proyecto resuelto.
+const SYSTEM_PROJECT_ACTIONS: &[&str] = &[
+    "tree",
+    "info",
+    "open",
+    "rename",
+    "link",
+    "move",
+    "unregister",
+    "delete",
+    "run",
+    "start",
+    "env",
+    "vars",
+    "scripts",
+    "which",
+    "exec",
+    "path",
+    "foreach",
+    "status",
+    "resolve",
+    "set",
+    "add",
+    "remove",
+    "logs",
+];
+/// Acciones de sistema que no requieren un contexto de proyecto.
+const SYSTEM_GLOBAL_ACTIONS: &[&str] = &[
+    "init",
+    "register",
+    "alias",
+    "doctor",
+    "list",
+    "version",
+    "templates",
+    "prune",
+    "self-update",
+];
 
 /// El punto de entrada principal de la aplicación.
 fn main() {
@@ -32,21 +92,53 @@ fn main() {
     // Esto se ejecuta en un hilo separado cuando se presiona Ctrl+C.
     ctrlc::set_handler(move || {
         r.store(false, Ordering::SeqCst);
+        // Detener el comando hijo en curso (si lo hay) en lugar de dejarlo huérfano.
+        axes::system::executor::notify_interrupted();
         println!("\nPor favor no intente cerrar forzosamente, puede cerrar de forma segura el shell usando `exit`.");
     }).expect("Error al establecer el manejador de Ctrl-C");
 
-    // Inicializar el logger.
-    env_logger::init();
-
     // Parsear los argumentos de la línea de comandos.
     let cli = Cli::parse();
 
+    output::set_level(if cli.quiet {
+        OutputLevel::Quiet
+    } else {
+        OutputLevel::Normal
+    });
+    let confirm_answer = if cli.yes {
+        Some(true)
+    } else if cli.no {
+        Some(false)
+    } else {
+        None
+    };
+    prompt::configure(cli.non_interactive, confirm_answer);
+
+    // Inicializar el logger según los flags (`-v`, `--log-file`) o `RUST_LOG`.
+    if let Err(e) = axes::system::logging::init(cli.verbose, cli.log_file.as_deref()) {
+        eprintln!("\nError: {}", e);
+        std::process::exit(1);
+    }
+
     // Ejecutar la lógica principal y manejar cualquier error.
     if let Err(e) = run_cli(cli) {
+        if let Some(answer) = e.downcast_ref::<InfoHasAnswer>() {
+            std::process::exit(answer.exit_code());
+        }
         // No mostrar el error si fue por una interrupción del usuario.
         if running.load(Ordering::SeqCst) {
             eprintln!("\nError: {:?}", e);
-            std::process::exit(1);
+            // Si falló un comando del proyecto, se propaga su código de salida (útil en CI).
+            let exit_code = e
+                .chain()
+                .find_map(|cause| {
+                    cause
+                        .downcast_ref::<ExecutionError>()
+                        .and_then(ExecutionError::exit_code)
+                        .or_else(|| cause.downcast_ref::<RunError>()?.exit_code())
+                })
+                .unwrap_or(1);
+            std::process::exit(exit_code);
         } else {
             // El error fue probablemente causado por la interrupción, así que salimos silenciosamente.
             println!("\nOperación cancelada.");
@@ -59,26 +151,28 @@ fn main() {
 fn run_cli(cli: Cli) -> Result<()> {
     log::debug!("CLI args parsed: {:?}", cli);
 
-    const SYSTEM_PROJECT_ACTIONS: &[&str] = &[
-        "tree",
-        "info",
-        "open",
-        "rename",
-        "link",
-        "unregister",
-        "delete",
-        "run",
-        "start",
-    ];
-    const SYSTEM_GLOBAL_ACTIONS: &[&str] = &["init", "register", "alias"];
+    if let Some(cwd) = &cli.cwd
+        && !cwd.is_dir()
+    {
+        return Err(anyhow!(
+            "La ruta de --cwd '{}' no es un directorio existente.",
+            cwd.display()
+        ));
+    }
+
+    if cli.version {
+        return handle_version();
+    }
+
+    if cli.validate {
+        handle_validate()?;
+    }
 
     // 1. Parseo Inicial
     let arg1 = match cli.context_or_action {
         Some(a) => a,
-        None => {
-            println!("TODO: Lanzar la TUI.");
-            return Ok(());
-        }
+        None if cli.validate => return Ok(()),
+        None => return handle_tui(),
     };
 
     let mut remaining_args = Vec::new();
@@ -97,6 +191,12 @@ fn run_cli(cli: Cli) -> Result<()> {
             "init" => handle_init(sub_command_or_context, final_args),
             "register" => handle_register(sub_command_or_context, final_args),
             "alias" => handle_alias(sub_command_or_context, final_args),
+            "doctor" => handle_doctor(sub_command_or_context, final_args),
+            "list" => handle_list(sub_command_or_context, final_args),
+            "version" => handle_version(),
+            "templates" => handle_templates(sub_command_or_context, final_args),
+            "prune" => handle_prune(sub_command_or_context, final_args),
+            "self-update" => handle_self_update(sub_command_or_context),
             _ => unreachable!(),
         };
     }
@@ -109,9 +209,27 @@ fn run_cli(cli: Cli) -> Result<()> {
 
         let project_uuid = Uuid::parse_str(&project_uuid_str)?;
         let index = index_manager::load_and_ensure_global_project()?;
+        // `resolve` solo necesita el índice: se atiende antes de resolver la configuración.
+        if action == "resolve" {
+            return match args.first().filter(|a| !a.starts_with("--")) {
+                Some(context) => {
+                    let (uuid, qualified_name) = context_resolver::resolve_context_from(
+                        context,
+                        &index,
+                        cli.cwd.as_deref(),
+                    )?;
+                    handle_resolve(uuid, &qualified_name, &index, args[1..].to_vec())
+                }
+                None => {
+                    let qualified_name = index_manager::build_qualified_name(project_uuid, &index)?;
+                    handle_resolve(project_uuid, &qualified_name, &index, args)
+                }
+            };
+        }
         let qualified_name =
-            index_manager::build_qualified_name(project_uuid, &index).ok_or_else(|| {
-                anyhow!("No se pudo reconstruir el nombre del proyecto de la sesión.")
+            index_manager::build_qualified_name(project_uuid, &index).map_err(|e| {
+                let remediation = session_name_remediation(&e);
+                anyhow!(e).context(remediation)
             })?;
         let config =
             config_resolver::resolve_config_for_uuid(project_uuid, qualified_name, &index)?;
@@ -128,30 +246,102 @@ fn run_cli(cli: Cli) -> Result<()> {
                 let context = arg2.cloned().ok_or_else(|| {
                     anyhow!("La acción '{}' requiere un contexto de proyecto.", arg1)
                 })?;
-                (context, arg1, remaining_args.into_iter().skip(1).collect())
+                (
+                    context,
+                    Some(arg1),
+                    remaining_args.into_iter().skip(1).collect(),
+                )
             } else {
-                // Formato: `axes <contexto> [acción?] [args...]`
+                // Formato: `axes <contexto> [acción?] [args...]`. Sin acción se usa la
+                // `default_action` del proyecto, que se conoce tras resolver su config.
                 let context = arg1;
-                let action = arg2.cloned().unwrap_or_else(|| "start".to_string());
+                let action = arg2.cloned();
                 let args = remaining_args.into_iter().skip(1).collect();
                 (context, action, args)
             };
 
         // `tree` sin contexto (o con `global`) es un caso especial
-        if action_str == "tree" && (context_str == "global" || context_str.is_empty()) {
-            return handle_tree(None);
+        if action_str.as_deref() == Some("tree")
+            && (context_str == "global" || context_str.is_empty())
+        {
+            return handle_tree(None, final_args);
+        }
+        // `axes tree <patrón>`: el "contexto" es un patrón glob sobre todo el árbol.
+        if action_str.as_deref() == Some("tree") && looks_like_name_pattern(&context_str) {
+            let mut pattern_args = vec![context_str];
+            pattern_args.extend(final_args);
+            return handle_tree(None, pattern_args);
+        }
+
+        // `axes unregister|delete --pattern <patrón>`: opera sobre todos los proyectos que
+        // coinciden. Es explícito a propósito: `a/*` sigue siendo el último hijo usado.
+        if let Some(bulk_action @ ("unregister" | "delete")) = action_str.as_deref() {
+            if context_str == "--pattern" {
+                let (pattern, rest) = final_args.split_first().ok_or_else(|| {
+                    anyhow!("'{} --pattern' requiere un patrón de nombres.", bulk_action)
+                })?;
+                let roots = resolve_bulk_targets(pattern)?;
+                return if bulk_action == "unregister" {
+                    unregister_projects(&roots, rest)
+                } else {
+                    delete_projects(&roots, rest)
+                };
+            }
+            if looks_like_name_pattern(&context_str) {
+                return Err(anyhow!(
+                    "'{}' es un patrón. Para operar sobre varios proyectos usa 'axes {} --pattern \"{}\"'.",
+                    context_str,
+                    bulk_action,
+                    context_str
+                ));
+            }
         }
 
         let index = index_manager::load_and_ensure_global_project()?;
-        let (uuid, qualified_name) = context_resolver::resolve_context(&context_str, &index)?;
+        let (uuid, qualified_name) =
+            context_resolver::resolve_context_from(&context_str, &index, cli.cwd.as_deref())?;
+        if action_str.as_deref() == Some("resolve") {
+            return handle_resolve(uuid, &qualified_name, &index, final_args);
+        }
         let config = config_resolver::resolve_config_for_uuid(uuid, qualified_name, &index)?;
 
+        let (action_str, final_args) = match action_str {
+            Some(action) => (action, final_args),
+            None => default_project_action(&config)?,
+        };
         execute_project_action(config, action_str, final_args, SYSTEM_PROJECT_ACTIONS)?;
     }
 
     Ok(())
 }
 
+/// La acción de `axes <contexto>` sin acción explícita, según `[options] default_action`:
+/// `start` si no está definida, o `run <script>` con `"run:<script>"`.
+fn default_project_action(config: &ResolvedConfig) -> Result<(String, Vec<String>)> {
+    match config.options.default_action.as_deref().map(str::trim) {
+        None | Some("start") => Ok(("start".to_string(), Vec::new())),
+        Some(action) => match action.strip_prefix("run:").map(str::trim) {
+            Some(script) if !script.is_empty() => Ok(("run".to_string(), vec![script.to_string()])),
+            _ => Err(anyhow!(
+                "'[options] default_action = \"{}\"' no es válido en '{}'. Usa \"start\" o \"run:<script>\".",
+                action,
+                config.qualified_name
+            )),
+        },
+    }
+}
+
+/// Mensaje con la solución para cuando no se puede reconstruir el nombre del proyecto de la
+/// sesión (el índice cambió desde que se abrió).
+fn session_name_remediation(error: &IndexError) -> String {
+    match error {
+        IndexError::BrokenParentLink { .. } => "Un ancestro del proyecto de la sesión ya no está registrado. Ejecuta `axes doctor --fix` para reenlazarlo a 'global', o enlázalo a otro padre con `axes <contexto> link <padre>`.".to_string(),
+        IndexError::ParentCycle { .. } => "La cadena de padres del proyecto de la sesión forma un ciclo. Ejecuta `axes doctor --fix` para romperlo.".to_string(),
+        IndexError::ProjectNotFoundInIndex { .. } => "El proyecto de la sesión ya no está registrado. Sal de la sesión con `exit` y vuelve a registrarlo con `axes register`.".to_string(),
+        _ => "No se pudo reconstruir el nombre del proyecto de la sesión.".to_string(),
+    }
+}
+
 /// Ejecuta una acción que opera sobre una configuración de proyecto ya resuelta.
 fn execute_project_action(
     config: ResolvedConfig,
@@ -166,12 +356,25 @@ fn execute_project_action(
     );
 
     match action.as_str() {
-        "tree" => handle_tree(Some(config)),
+        "tree" => handle_tree(Some(config), args),
         "start" => handle_start(&config),
-        "info" => handle_info(&config),
+        "info" => handle_info(&config, args),
+        "env" => handle_env(&config, args),
+        "vars" => handle_vars(&config, args),
+        "scripts" => handle_scripts(&config, args),
+        "which" => handle_which(&config, args),
+        "exec" => handle_exec(&config, args),
+        "path" => handle_path(&config, args),
+        "foreach" => handle_foreach(&config, args),
+        "status" => handle_status(&config, args),
         "open" => handle_open(&config, args),
+        "logs" => handle_logs(&config, args),
         "rename" => handle_rename(&config, args),
+        "set" => handle_set(&config, args),
+        "add" => handle_add(&config, args),
+        "remove" => handle_remove(&config, args),
         "link" => handle_link(&config, args),
+        "move" => handle_move(&config, args),
         "unregister" => handle_unregister(&config, args),
         "delete" => handle_delete(&config, args),
         "run" => {
@@ -195,18 +398,94 @@ fn execute_project_action(
 // --- MANEJADORES DE ACCIONES (Implementaciones) ---
 
 ///Permite crear y registrar nuevos proyectos a axes.
-fn handle_init(name_arg: Option<String>, args: Vec<String>) -> Result<()> {
-    let project_name = name_arg
-        .ok_or_else(|| anyhow!("El comando 'init' requiere un nombre para el nuevo proyecto."))?;
+/// Sin nombre (o con `--interactive`) pregunta los datos del proyecto con un asistente.
+fn handle_init(name_arg: Option<String>, mut args: Vec<String>) -> Result<()> {
+    // Un flag en la posición del nombre (ej. `axes init --interactive`) no es el nombre.
+    let name_arg = match name_arg {
+        Some(flag) if flag.starts_with("--") => {
+            args.insert(0, flag);
+            None
+        }
+        other => other,
+    };
+
+    // `axes init --list-templates` no necesita nombre.
+    if args.iter().any(|a| a == "--list-templates") {
+        return print_templates();
+    }
 
-    // Parseo simple de argumentos para --parent
+    // Parseo simple de argumentos para --parent, --template y --template-repo
+    // `--parent` pasa por el resolvedor de contextos normal, así que `--parent .` es el
+    // proyecto registrado más cercano que contiene el directorio actual.
     let mut parent_context: Option<String> = None;
     if let Some(pos) = args.iter().position(|r| r == "--parent") {
-        parent_context = args.get(pos + 1).cloned();
+        parent_context = Some(
+            args.get(pos + 1)
+                .cloned()
+                .ok_or_else(|| anyhow!("'--parent' requiere un contexto (ej. `--parent .`)."))?,
+        );
+    }
+    let mut template_name: Option<String> = None;
+    if let Some(pos) = args.iter().position(|r| r == "--template") {
+        template_name = Some(
+            args.get(pos + 1)
+                .cloned()
+                .ok_or_else(|| anyhow!("'--template' requiere el nombre de una plantilla."))?,
+        );
+    }
+    let mut template_repo: Option<String> = None;
+    if let Some(pos) = args.iter().position(|r| r == "--template-repo") {
+        template_repo = Some(args.get(pos + 1).cloned().ok_or_else(|| {
+            anyhow!("'--template-repo' requiere la URL de un repositorio git de plantillas.")
+        })?);
+    }
+    let mut template_ref: Option<String> = None;
+    if let Some(pos) = args.iter().position(|r| r == "--template-ref") {
+        template_ref = Some(
+            args.get(pos + 1)
+                .cloned()
+                .ok_or_else(|| anyhow!("'--template-ref' requiere una rama o etiqueta."))?,
+        );
+    }
+    if template_ref.is_some() && template_repo.is_none() {
+        anyhow::bail!("'--template-ref' solo tiene sentido junto a '--template-repo'.");
     }
+    let template = match (&template_repo, template_name.as_deref()) {
+        (Some(_), None) => {
+            anyhow::bail!(
+                "'--template-repo' requiere elegir la plantilla con '--template <nombre>'."
+            )
+        }
+        (Some(url), Some(name)) => {
+            status!("Obteniendo plantillas de '{}'...", url);
+            let repo_dir = templates::fetch_template_repo(url, template_ref.as_deref())?;
+            Some(templates::resolve_repo_template(&repo_dir, name)?)
+        }
+        (None, name) => name.map(templates::resolve_template).transpose()?,
+    };
+    let template_vars = parse_template_vars(&args)?;
+    let strict_template = args.iter().any(|a| a == "--strict-template");
+    let init_git = args.iter().any(|a| a == "--git");
 
     let current_dir = env::current_dir()?;
-    println!(
+
+    let wizard = if name_arg.is_none() || args.iter().any(|a| a == "--interactive") {
+        Some(run_init_wizard(
+            name_arg.as_deref(),
+            &current_dir,
+            parent_context.is_none(),
+        )?)
+    } else {
+        None
+    };
+    let project_name = match (&wizard, name_arg) {
+        (Some(answers), _) => answers.name.clone(),
+        (None, Some(name)) => name,
+        (None, None) => unreachable!("sin nombre siempre se usa el asistente"),
+    };
+    index_manager::validate_project_name(&project_name)?;
+
+    status!(
         "Inicializando proyecto '{}' en {}",
         project_name,
         current_dir.display()
@@ -221,37 +500,73 @@ fn handle_init(name_arg: Option<String>, args: Vec<String>) -> Result<()> {
     }
 
     // 2. Cargar índice y resolver el padre (si se especificó)
+    // Bloquear el índice durante toda la secuencia cargar-modificar-guardar.
+    let _lock = index_manager::lock_global_index()?;
     let mut index = index_manager::load_and_ensure_global_project()?;
-    let final_parent_uuid: Uuid = match parent_context {
-        Some(context) => {
-            println!("Resolviendo padre '{}'...", context);
+    let wizard_parent = wizard.as_ref().and_then(|answers| answers.parent);
+    let final_parent_uuid: Uuid = match (wizard_parent, parent_context) {
+        (Some(uuid), _) => uuid,
+        (None, Some(context)) => {
+            status!("Resolviendo padre '{}'...", context);
             let (uuid, qualified_name) = context_resolver::resolve_context(&context, &index)?;
-            println!(
+            status!(
                 "Proyecto padre '{}' encontrado (UUID: {}).",
-                qualified_name, uuid
+                qualified_name,
+                uuid
             );
             uuid
         }
-        None => {
-            println!(
-                "No se especificó padre. Se enlazará al proyecto 'global'. (UUID: {})",
+        (None, None) => match detect_parent_from_path(&current_dir, &index) {
+            Some(uuid) => {
+                status!(
+                    "No se especificó padre. Se enlazará a '{}', el proyecto más cercano que contiene este directorio ({}). Usa --parent para elegir otro.",
+                    index_manager::build_qualified_name(uuid, &index).unwrap_or_default(),
+                    index.projects[&uuid].path.display()
+                );
+                uuid
+            }
+            None => {
+                status!(
+                    "No se especificó padre y ningún proyecto registrado contiene este directorio. Se enlazará al proyecto 'global'. (UUID: {})",
+                    index_manager::GLOBAL_PROJECT_UUID
+                );
                 index_manager::GLOBAL_PROJECT_UUID
-            );
-            index_manager::GLOBAL_PROJECT_UUID
-        }
+            }
+        },
     };
 
     // 3. Añadir el nuevo proyecto al índice
-    let canonical_path = current_dir.canonicalize()?;
+    let canonical_path = dunce::canonicalize(&current_dir)?;
     let (new_uuid, _) = index_manager::add_project_to_index(&mut index, project_name.clone(), canonical_path.clone(), Some(final_parent_uuid))
         .context("No se pudo añadir el proyecto al índice global. Podría haber un proyecto hermano con el mismo nombre.")?;
 
-    // 4. Crear la estructura de archivos del proyecto en el disco
+    // 4. Crear la estructura de archivos del proyecto en el disco.
+    // La plantilla (si hay) va primero: puede traer su propio `.axes/axes.toml`.
+    let defaults = ProjectConfig::new();
+    let (description, version) = match &wizard {
+        Some(answers) => (answers.description.clone(), answers.version.clone()),
+        None => (
+            defaults.description.unwrap_or_default(),
+            defaults.version.unwrap_or_default(),
+        ),
+    };
+    let created_files = match (&template, &template_name) {
+        (Some(source), Some(name)) => {
+            let mut context = templates::base_context(&project_name, &version, &description);
+            context.extend(template_vars);
+            templates::apply_template(source, &current_dir, &context, strict_template)
+                .context(format!("No se pudo aplicar la plantilla '{}'.", name))?
+        }
+        _ => Vec::new(),
+    };
     fs::create_dir_all(&axes_dir)?;
     let config_path = axes_dir.join(PROJECT_CONFIG_FILENAME);
-    let default_config = ProjectConfig::new();
-    let toml_string = toml::to_string_pretty(&default_config)?;
-    fs::write(&config_path, toml_string)?;
+    if !config_path.exists() {
+        fs::write(
+            &config_path,
+            ProjectConfig::default_toml(&description, &version),
+        )?;
+    }
 
     // 5. Crear y guardar el archivo de referencia local (`project_ref.bin`)
     let project_ref = ProjectRef {
@@ -265,40 +580,215 @@ fn handle_init(name_arg: Option<String>, args: Vec<String>) -> Result<()> {
     // 6. Guardar el índice global actualizado
     index_manager::save_global_index(&index)
         .context("No se pudo guardar el índice global actualizado.")?;
+    index_manager::invalidate_child_caches(&index, [final_parent_uuid]);
 
-    println!("\n✔ ¡Éxito!");
-    println!(
+    status!("\n{} ¡Éxito!", output::ok_mark());
+    status!(
         "  Proyecto '{}' creado con UUID: {}",
-        project_name, new_uuid
+        project_name,
+        new_uuid
     );
-    println!("  Configuración creada en: {}", config_path.display());
-    println!(
+    status!("  Configuración creada en: {}", config_path.display());
+    status!(
         "  Identidad local guardada en: .axes/{}",
         axes::constants::PROJECT_REF_FILENAME
     );
-    println!("  Registrado correctamente en el índice global.");
+    status!("  Registrado correctamente en el índice global.");
+    if let Some(name) = &template_name {
+        status!(
+            "  Plantilla '{}' aplicada ({} archivos creados).",
+            name,
+            created_files.len()
+        );
+    }
+
+    // 7. Opcional: repositorio git y `.gitignore` para las cachés de axes.
+    if init_git {
+        init_git_repository(&canonical_path);
+    }
 
     Ok(())
 }
 
+/// Respuestas del asistente interactivo de `init`.
+struct InitWizardAnswers {
+    name: String,
+    description: String,
+    version: String,
+    /// `None` si el padre se indicó con `--parent`.
+    parent: Option<Uuid>,
+}
+
+/// Pregunta nombre, descripción, versión y (si no se pasó `--parent`) el proyecto padre.
+fn run_init_wizard(
+    name: Option<&str>,
+    current_dir: &Path,
+    ask_parent: bool,
+) -> Result<InitWizardAnswers> {
+    prompt::require_interactive("asistente de `init`; indica el nombre: `axes init <nombre>`")?;
+    let theme = output::theme();
+    let defaults = ProjectConfig::new();
+    let default_name = name.map(str::to_string).unwrap_or_else(|| {
+        current_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+
+    let name: String = Input::with_theme(&*theme)
+        .with_prompt("Nombre del proyecto")
+        .default(default_name)
+        .validate_with(|input: &String| index_manager::validate_project_name(input))
+        .interact_text()?;
+    let description: String = Input::with_theme(&*theme)
+        .with_prompt("Descripción")
+        .default(defaults.description.unwrap_or_default())
+        .interact_text()?;
+    let version: String = Input::with_theme(&*theme)
+        .with_prompt("Versión")
+        .default(defaults.version.unwrap_or_default())
+        .interact_text()?;
+
+    let parent = if ask_parent {
+        // Solo lectura: el índice se bloquea después, para no retenerlo durante las preguntas.
+        let index = index_manager::load_and_ensure_global_project()?;
+        let items = graph_display::build_tree_items(&index);
+        let labels: Vec<&str> = items.iter().map(|(_, label)| label.as_str()).collect();
+        let suggested = detect_parent_from_path(current_dir, &index)
+            .unwrap_or(index_manager::GLOBAL_PROJECT_UUID);
+        let default_pos = items
+            .iter()
+            .position(|(uuid, _)| *uuid == suggested)
+            .unwrap_or(0);
+        let selection = Select::with_theme(&*theme)
+            .with_prompt("Proyecto padre")
+            .items(&labels)
+            .default(default_pos)
+            .interact()?;
+        Some(items[selection].0)
+    } else {
+        None
+    };
+
+    Ok(InitWizardAnswers {
+        name,
+        description,
+        version,
+        parent,
+    })
+}
+
+/// Busca, subiendo por el árbol de directorios, el proyecto registrado más cercano que
+/// contiene `dir`. Se usa como padre por defecto en `init`.
+fn detect_parent_from_path(dir: &Path, index: &GlobalIndex) -> Option<Uuid> {
+    context_resolver::find_project_from_path(dir, true, index).ok()
+}
+
+/// Patrones de `.gitignore` para los archivos generados por axes. `axes.toml` y
+/// `project_ref.bin` deben versionarse, así que no se incluyen.
+const AXES_GITIGNORE_PATTERNS: &[&str] = &[".axes/*.cache.bin"];
+
+/// Ejecuta `git init` (si aún no es un repositorio) y añade las cachés de axes al `.gitignore`.
+/// Los fallos solo se muestran como advertencia: el proyecto ya está creado.
+fn init_git_repository(project_root: &Path) {
+    if project_root.join(".git").exists() {
+        status!("  Ya existe un repositorio git; no se ejecuta 'git init'.");
+    } else if let Err(e) =
+        axes::system::executor::execute_command("git init --quiet", project_root, &HashMap::new())
+    {
+        eprintln!(
+            "  Advertencia: no se pudo inicializar el repositorio git (¿está git instalado?): {}",
+            e
+        );
+    } else {
+        status!("  Repositorio git inicializado.");
+    }
+
+    let gitignore_path = project_root.join(".gitignore");
+    let existing = fs::read_to_string(&gitignore_path).unwrap_or_default();
+    let missing: Vec<&str> = AXES_GITIGNORE_PATTERNS
+        .iter()
+        .copied()
+        .filter(|pattern| !existing.lines().any(|line| line.trim() == *pattern))
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+
+    let mut content = existing;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str("# Cachés generadas por axes\n");
+    for pattern in missing {
+        content.push_str(pattern);
+        content.push('\n');
+    }
+    match fs::write(&gitignore_path, content) {
+        Ok(()) => status!("  Cachés de axes añadidas a .gitignore."),
+        Err(e) => eprintln!("  Advertencia: no se pudo actualizar .gitignore: {}", e),
+    }
+}
+
+/// Cambia el padre de un proyecto. Con `--detach` (o `link global`) lo cuelga de `global`.
+/// Con `--dry-run` solo comprueba si el enlace es válido y muestra los nombres resultantes.
 fn handle_link(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
-    // 1. Obtener el contexto del nuevo padre.
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+
+    // 1. Obtener el contexto del nuevo padre. `--detach` equivale a `global`.
     let new_parent_context = args
-        .first()
-        .ok_or_else(|| anyhow!("El comando 'link' requiere el contexto del nuevo padre."))?
+        .iter()
+        .find(|a| a.as_str() != "--dry-run")
+        .ok_or_else(|| {
+            anyhow!("El comando 'link' requiere el contexto del nuevo padre (o '--detach').")
+        })?
         .trim();
+    let new_parent_context = if new_parent_context == "--detach" {
+        "global"
+    } else {
+        new_parent_context
+    };
 
     if new_parent_context.is_empty() {
         return Err(anyhow!("El contexto del nuevo padre no puede estar vacío."));
     }
     // No validamos caracteres de ruta aquí porque es un contexto, no un nombre directo.
 
-    println!(
+    if dry_run {
+        let index = index_manager::load_and_ensure_global_project()?;
+        let (new_parent_uuid, _) = context_resolver::resolve_context(new_parent_context, &index)
+            .context(format!(
+                "No se pudo resolver el contexto del nuevo padre '{}'.",
+                new_parent_context
+            ))?;
+        let changes = index_manager::preview_link(&index, config.uuid, new_parent_uuid)
+            .with_context(|| {
+                format!(
+                    "No se podría enlazar el proyecto '{}' bajo '{}'.",
+                    config.qualified_name, new_parent_context
+                )
+            })?;
+        println!("Vista previa (no se ha guardado ningún cambio):");
+        for (_, old_name, new_name) in &changes {
+            println!("  {}  ->  {}", old_name, new_name);
+        }
+        if changes.len() > 1 {
+            println!(
+                "\n{} descendiente(s) cambiarían también de nombre cualificado.",
+                changes.len() - 1
+            );
+        }
+        return Ok(());
+    }
+
+    status!(
         "Intentando mover '{}' a ser hijo de '{}'...",
-        config.qualified_name, new_parent_context
+        config.qualified_name,
+        new_parent_context
     );
 
     // 2. Cargar el índice global y resolver el UUID del nuevo padre.
+    let _lock = index_manager::lock_global_index()?;
     let mut index = index_manager::load_and_ensure_global_project()?;
     let (new_parent_uuid, new_parent_qualified_name) =
         context_resolver::resolve_context(new_parent_context, &index).context(format!(
@@ -309,14 +799,22 @@ fn handle_link(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
     // 3. Validaciones críticas (en el `index_manager`):
     //    a. Anti-Ciclos
     //    b. Anti-Colisión de Nombres de Hermano
+    let old_parent_uuid = index
+        .projects
+        .get(&config.uuid)
+        .and_then(|entry| entry.parent);
     index_manager::link_project(&mut index, config.uuid, new_parent_uuid).context(format!(
         "No se pudo establecer el enlace para el proyecto '{}'.",
         config.qualified_name
     ))?;
 
-    // 4. Guardar el índice global modificado.
+    // 4. Guardar el índice global modificado. El padre anterior y el nuevo cambian de hijos.
     index_manager::save_global_index(&index)
         .context("No se pudo guardar el índice global actualizado.")?;
+    index_manager::invalidate_child_caches(
+        &index,
+        old_parent_uuid.into_iter().chain([new_parent_uuid]),
+    );
 
     // 5. Actualizar el `project_ref.bin` local (usando `get_or_create_project_ref`)
     let mut project_ref =
@@ -333,19 +831,141 @@ fn handle_link(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
         );
     }
 
-    println!("\n✔ ¡Éxito!");
-    println!(
+    status!("\n{} ¡Éxito!", output::ok_mark());
+    status!(
         "El proyecto '{}' ahora es hijo de '{}'.",
-        config.qualified_name, new_parent_qualified_name
+        config.qualified_name,
+        new_parent_qualified_name
+    );
+    status!("Nota: los cachés se regenerarán automáticamente en la próxima resolución.");
+
+    Ok(())
+}
+
+/// Mueve un proyecto a otra ubicación en el disco y actualiza el índice.
+/// Por defecto solo se mueve el directorio `.axes`; con `--whole` se mueve el proyecto entero.
+fn handle_move(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let mut move_whole_project = false;
+    let mut destination_arg: Option<String> = None;
+    for arg in args {
+        if arg == "--whole" {
+            move_whole_project = true;
+        } else if destination_arg.is_none() {
+            destination_arg = Some(arg);
+        } else {
+            return Err(anyhow!(
+                "Argumento inesperado '{}' para el comando 'move'.",
+                arg
+            ));
+        }
+    }
+    let destination_arg = destination_arg
+        .ok_or_else(|| anyhow!("El comando 'move' requiere la nueva ruta del proyecto."))?;
+
+    if config.uuid == index_manager::GLOBAL_PROJECT_UUID {
+        return Err(anyhow!("El proyecto 'global' no se puede mover."));
+    }
+
+    let destination = env::current_dir()?.join(destination_arg.trim());
+    if destination.join(AXES_DIR).exists() {
+        return Err(anyhow!(
+            "El destino '{}' ya contiene un directorio '.axes'.",
+            destination.display()
+        ));
+    }
+
+    let old_root = config.project_root.clone();
+    let (source, target) = if move_whole_project {
+        if destination.exists() {
+            return Err(anyhow!(
+                "El destino '{}' ya existe. Para mover el proyecto entero debe ser una ruta nueva.",
+                destination.display()
+            ));
+        }
+        (old_root.clone(), destination.clone())
+    } else {
+        fs::create_dir_all(&destination)?;
+        (old_root.join(AXES_DIR), destination.join(AXES_DIR))
+    };
+
+    let _lock = index_manager::lock_global_index()?;
+    let mut index = index_manager::load_and_ensure_global_project()?;
+
+    status!(
+        "Moviendo '{}' de {} a {}...",
+        config.qualified_name,
+        source.display(),
+        target.display()
+    );
+    fs::rename(&source, &target).with_context(|| {
+        format!(
+            "No se pudo mover '{}' a '{}'.",
+            source.display(),
+            target.display()
+        )
+    })?;
+
+    let new_root = match index_manager::relocate_project(&mut index, config.uuid, &destination) {
+        Ok(path) => path,
+        Err(e) => {
+            // Deshacer el movimiento para no dejar el índice y el disco desincronizados.
+            if let Err(rollback_err) = fs::rename(&target, &source) {
+                eprintln!(
+                    "Advertencia: no se pudo deshacer el movimiento de '{}': {}",
+                    target.display(),
+                    rollback_err
+                );
+            }
+            return Err(anyhow!(e).context(format!(
+                "No se pudo actualizar la ruta de '{}' en el índice global.",
+                config.qualified_name
+            )));
+        }
+    };
+
+    if move_whole_project {
+        let rebased = index_manager::rebase_nested_project_paths(&mut index, &old_root, &new_root);
+        if !rebased.is_empty() {
+            status!(
+                "Se actualizaron las rutas de {} proyectos anidados.",
+                rebased.len()
+            );
+        }
+    }
+
+    index_manager::save_global_index(&index)
+        .context("No se pudo guardar el índice global actualizado.")?;
+
+    let project_ref = ProjectRef {
+        self_uuid: config.uuid,
+        parent_uuid: index.projects.get(&config.uuid).and_then(|e| e.parent),
+        name: index
+            .projects
+            .get(&config.uuid)
+            .map(|e| e.name.clone())
+            .unwrap_or_default(),
+    };
+    if let Err(e) = index_manager::write_project_ref(&new_root, &project_ref) {
+        eprintln!(
+            "\nAdvertencia: El proyecto fue movido, pero no se pudo reescribir el archivo de referencia local `project_ref.bin`: {}",
+            e
+        );
+    }
+
+    status!("\n{} ¡Éxito!", output::ok_mark());
+    status!(
+        "El proyecto '{}' ahora está en {}",
+        config.qualified_name,
+        new_root.display()
     );
-    println!("Nota: los cachés se regenerarán automáticamente en la próxima resolución.");
+    status!("Nota: los cachés se regenerarán automáticamente en la próxima resolución.");
 
     Ok(())
 }
 
 /// Inicia una sesión de terminal interactiva para el proyecto.
 fn handle_start(config: &ResolvedConfig) -> Result<()> {
-    println!("\nIniciando sesión para '{}'...", config.qualified_name);
+    status!("\nIniciando sesión para '{}'...", config.qualified_name);
 
     // Simplemente llamamos a nuestra nueva función.
     // Usamos `with_context` para añadir información útil al error si ocurre.
@@ -358,98 +978,1022 @@ fn handle_start(config: &ResolvedConfig) -> Result<()> {
 }
 
 /// Ejecuta un comando definido en el `axes.toml` del proyecto.
+/// Antes se ejecutan sus prerrequisitos (`depends_on`), cada uno una sola vez.
+/// Los scripts con `confirm` piden confirmación antes de empezar, salvo que se pase `--yes`
+/// (antes de `--`).
+/// Con `--all a b c` se ejecutan varios scripts en orden (sin parámetros), parando en el primer fallo.
+/// Con `--json` se imprime al final un resumen de cada comando ejecutado (implica `--quiet`);
+/// la salida de los comandos va a stderr para que stdout sea JSON válido.
+/// Con `--print-only` no se ejecuta nada: solo se imprimen los comandos, uno por línea.
+/// Lo que sigue a `--` se pasa tal cual al script, aunque coincida con un flag de axes.
 fn handle_run(
     config: &ResolvedConfig,
     script_name: Option<String>,
-    params: Vec<String>,
+    mut params: Vec<String>,
 ) -> Result<()> {
-    let script_key = script_name
-        .ok_or_else(|| anyhow!("Debe especificar un script para ejecutar con 'run'."))?;
+    // Un flag en la posición del script (ej. `run --yes`) no es el script.
+    let script_name = match script_name {
+        Some(flag) if flag.starts_with("--") && flag != "--all" => {
+            params.insert(0, flag);
+            None
+        }
+        other => other,
+    };
+    // Sin script, en una terminal se elige de una lista.
+    let script_key = match script_name {
+        Some(name) => name,
+        None if prompt::is_interactive() => match pick_script(config)? {
+            Some(name) => name,
+            None => {
+                println!("Operación cancelada.");
+                return Ok(());
+            }
+        },
+        None => {
+            return Err(anyhow!(
+                "Debe especificar un script para ejecutar con 'run'."
+            ));
+        }
+    };
 
-    let command_def = config.commands.get(&script_key).ok_or_else(|| {
-        anyhow!(
-            "Script '{}' no encontrado en la configuración del proyecto.",
-            script_key
-        )
-    })?;
+    let [skip_confirm, json, print_only, time] =
+        take_run_switches(&mut params, ["--yes", "--json", "--print-only", "--time"]);
+    let watch_options = extract_watch_options(config, &mut params)?;
+    if json && watch_options.is_some() {
+        return Err(anyhow!("'--json' no se puede combinar con '--watch'."));
+    }
+    if print_only && (json || watch_options.is_some()) {
+        return Err(anyhow!(
+            "'--print-only' no se puede combinar con '--json' ni con '--watch'."
+        ));
+    }
+    if time && (json || print_only) {
+        return Err(anyhow!(
+            "'--time' no se puede combinar con '--json' (que ya incluye las duraciones) ni con '--print-only'."
+        ));
+    }
+    if json {
+        // stdout queda solo para el resumen final; la salida de los comandos va a stderr.
+        output::set_level(OutputLevel::Quiet);
+        axes::system::executor::redirect_stdout_to_stderr(true);
+    }
 
-    // 1. Obtener el `Runnable` de la definición del comando.
-    let runnable_template = match command_def {
-        ProjectCommand::Sequence(s) => Runnable::Sequence(s.clone()),
-        ProjectCommand::Simple(s) => Runnable::Single(s.clone()),
-        ProjectCommand::Extended(ext) => ext.run.clone(),
-        ProjectCommand::Platform(pc) => {
-            let os_specific_runnable = if cfg!(target_os = "windows") {
-                pc.windows.as_ref()
-            } else if cfg!(target_os = "linux") {
-                pc.linux.as_ref()
-            } else if cfg!(target_os = "macos") {
-                pc.macos.as_ref()
-            } else {
-                None
-            };
+    // `--env CLAVE=VALOR` se aplica sobre el `[env]` del proyecto solo en esta ejecución.
+    let env_overrides = extract_env_overrides(&mut params)?;
+    let config_with_overrides;
+    let config = if env_overrides.is_empty() {
+        config
+    } else {
+        let mut overridden = config.clone();
+        overridden.env.extend(env_overrides);
+        config_with_overrides = overridden;
+        &config_with_overrides
+    };
+
+    // `--cwd <subdir>` cambia el directorio de trabajo solo en esta ejecución.
+    let workdir = extract_cwd_override(config, &mut params)?;
+    if workdir != config.project_root {
+        status!("Directorio de trabajo: {}", workdir.display());
+    }
+
+    // Ya extraídos los flags de axes, el separador `--` no forma parte de los parámetros.
+    let separator = params.iter().position(|p| p == "--");
+    if let Some(i) = separator {
+        params.remove(i);
+    }
 
-            os_specific_runnable.or(pc.default.as_ref())
-                .ok_or_else(|| anyhow!("El script '{}' no tiene una implementación para el SO actual y no tiene un 'default'.", script_key))?
-                .clone()
+    // En modo `--all` no se puede distinguir un parámetro de un script: no hay parámetros.
+    let run_all = script_key == "--all";
+    let (requested, params) = if run_all {
+        if separator.is_some() {
+            return Err(anyhow!(
+                "'run --all' no admite parámetros para los scripts (tras '--')."
+            ));
+        }
+        if params.is_empty() {
+            return Err(anyhow!(
+                "'run --all' requiere al menos un script (ej: 'run --all build test')."
+            ));
         }
+        (params, Vec::new())
+    } else {
+        (vec![script_key], params)
     };
 
-    // 2. Ejecutar el `Runnable`.
-    let interpolator = axes::core::interpolator::Interpolator::new(config, &params);
+    for name in &requested {
+        if !config.commands.contains_key(name) {
+            return Err(anyhow!(
+                "Script '{}' no encontrado en la configuración del proyecto.",
+                name
+            ));
+        }
+    }
+
+    let execution_order = resolve_script_order(config, &requested)?;
 
-    match runnable_template {
-        Runnable::Single(command_template) => {
-            let final_command = interpolator.interpolate(&command_template);
-            println!("\n> {}", final_command);
-            axes::system::executor::execute_command(
-                &final_command,
-                &config.project_root,
-                &config.env,
-            )
-            .map_err(|e| anyhow!(e))?;
+    // `--print-only`: los comandos tal cual, sin decoración, para `eval "$(...)"`.
+    if print_only {
+        for name in &execution_order {
+            let script_params: &[String] = if requested.contains(name) && !run_all {
+                &params
+            } else {
+                &[]
+            };
+            for command in runner::script_commands(config, name, script_params)? {
+                println!("{}", command);
+            }
         }
-        Runnable::Sequence(command_templates) => {
-            println!(
-                "\nEjecutando secuencia de comandos para '{}'...",
-                script_key
-            );
-            for (i, command_template) in command_templates.iter().enumerate() {
-                let final_command = interpolator.interpolate(command_template);
-                println!(
-                    "\n[{}/{}]> {}",
-                    i + 1,
-                    command_templates.len(),
-                    final_command
-                );
+        return Ok(());
+    }
 
-                // Si cualquier paso falla, `?` detendrá la ejecución y propagará el error.
-                axes::system::executor::execute_command(
-                    &final_command,
-                    &config.project_root,
-                    &config.env,
-                )
-                .map_err(|e| anyhow!(e))?;
+    // Se confirma todo antes de ejecutar nada, para no dejar una cadena a medias.
+    if !skip_confirm {
+        for name in &execution_order {
+            let Some(ProjectCommand::Extended(ext)) = config.commands.get(name) else {
+                continue;
+            };
+            let Some(question) = &ext.confirm else {
+                continue;
+            };
+            if !prompt::confirm(question, false)? {
+                println!("Operación cancelada.");
+                return Ok(());
             }
-            println!("\n✔ Secuencia completada con éxito.");
         }
     }
 
+    let run_all_scripts = |report: &mut RunReport| -> Result<()> {
+        for name in &execution_order {
+            // Los parámetros solo se pasan al script invocado explícitamente.
+            if !requested.contains(name) {
+                status!("\n--- Prerrequisito '{}' ---", name);
+                runner::run_script_in(config, name, &[], &workdir, report)?;
+            } else if run_all {
+                status!("\n--- Script '{}' ---", name);
+                runner::run_script_in(config, name, &[], &workdir, report)?;
+            } else {
+                runner::run_script_in(config, name, &params, &workdir, report)?;
+            }
+        }
+        Ok(())
+    };
+
+    let Some(watch_options) = watch_options else {
+        let mut report = RunReport::default();
+        let result = run_all_scripts(&mut report);
+        // El resumen se imprime también si algo falló: el último paso es el que falló.
+        if json {
+            print_run_report(&report)?;
+        }
+        if time {
+            print_run_timings(&report);
+        }
+        return result;
+    };
+
+    // En modo `--watch` un fallo no termina el bucle: se muestra y se espera al siguiente cambio.
+    axes::system::watcher::watch(&config.project_root, &watch_options, || {
+        let mut report = RunReport::default();
+        let result = run_all_scripts(&mut report);
+        if time {
+            print_run_timings(&report);
+        }
+        if let Err(e) = result
+            && !axes::system::executor::was_interrupted()
+        {
+            eprintln!("\n{} {:#}", output::fail_mark(), e);
+        }
+        status!("\n--- Esperando cambios (Ctrl+C para salir) ---");
+    })?;
     Ok(())
 }
 
-/// Muestra información detallada sobre la configuración resuelta del proyecto.
-fn handle_info(config: &ResolvedConfig) -> Result<()> {
-    let config_file_path = config
-        .project_root
-        .join(AXES_DIR)
-        .join(PROJECT_CONFIG_FILENAME);
-
+/// Muestra los scripts ejecutables en esta plataforma (con su descripción) y devuelve
+/// el elegido, o `None` si se cancela la selección.
+fn pick_script(config: &ResolvedConfig) -> Result<Option<String>> {
+    let scripts: Vec<(&String, Option<&str>)> = config
+        .commands
+        .iter()
+        .filter(|(_, command)| is_command_available(command))
+        .map(|(name, command)| {
+            let desc = match command {
+                ProjectCommand::Extended(ext) => ext.desc.as_deref(),
+                ProjectCommand::Platform(pc) => pc.desc.as_deref(),
+                ProjectCommand::Simple(_) | ProjectCommand::Sequence(_) => None,
+            };
+            (name, desc)
+        })
+        .collect();
+    if scripts.is_empty() {
+        return Err(anyhow!(
+            "'{}' no tiene scripts que se puedan ejecutar en esta plataforma.",
+            config.qualified_name
+        ));
+    }
+
+    let items: Vec<String> = scripts
+        .iter()
+        .map(|(name, desc)| match desc {
+            Some(desc) => format!("{} - {}", name, desc),
+            None => name.to_string(),
+        })
+        .collect();
+    let selection = Select::with_theme(&*output::theme())
+        .with_prompt(format!("Script de '{}' a ejecutar", config.qualified_name))
+        .items(&items)
+        .default(0)
+        .interact_opt()?;
+    Ok(selection.map(|i| scripts[i].0.clone()))
+}
+
+/// Imprime la duración de cada comando ejecutado (`run --time`) y, si hubo más de uno,
+/// el total.
+fn print_run_timings(report: &RunReport) {
+    println!("\nTiempos:");
+    for step in &report.steps {
+        let mark = if step.success {
+            output::ok_mark()
+        } else {
+            output::fail_mark()
+        };
+        let phase = match step.phase {
+            runner::StepPhase::Run => "",
+            runner::StepPhase::Pre => " [pre]",
+            runner::StepPhase::Post => " [post]",
+        };
+        println!(
+            "  {} {}{} ({:.2}s)  > {}",
+            mark,
+            step.script,
+            phase,
+            step.duration.as_secs_f64(),
+            step.command
+        );
+    }
+    if report.steps.len() > 1 {
+        println!("  Total: {:.2}s", report.duration().as_secs_f64());
+    }
+}
+
+/// Imprime el resumen de `run --json`: éxito global, duración total y cada comando.
+fn print_run_report(report: &RunReport) -> Result<()> {
+    let summary = serde_json::json!({
+        "success": report.success(),
+        "duration_ms": report.duration().as_millis() as u64,
+        "steps": report.steps,
+    });
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+    Ok(())
+}
+
+/// Quita de `params` los flags de `run` sin valor de `switches` e indica cuáles estaban. Solo
+/// se buscan antes de `--`: un `--yes` tras el separador es del script (ej. `apt-get`).
+fn take_run_switches<const N: usize>(params: &mut Vec<String>, switches: [&str; N]) -> [bool; N] {
+    let passthrough = split_passthrough(params);
+    let found = switches.map(|switch| params.iter().any(|p| p == switch));
+    params.retain(|p| !switches.contains(&p.as_str()));
+    params.extend(passthrough);
+    found
+}
+
+/// Separa de `params` lo que sigue al primer `--` (separador incluido): son argumentos del
+/// script, no flags de `run`. Quien extraiga flags debe volver a añadirlos al final.
+fn split_passthrough(params: &mut Vec<String>) -> Vec<String> {
+    match params.iter().position(|p| p == "--") {
+        Some(i) => params.split_off(i),
+        None => Vec::new(),
+    }
+}
+
+/// Extrae de los parámetros `--cwd <ruta>` (o `--cwd=<ruta>`) y devuelve el directorio de
+/// trabajo de la ejecución: la ruta, relativa a la raíz del proyecto, o la propia raíz si no
+/// se indicó. Tiene que ser un directorio existente dentro del proyecto. Lo que sigue a `--`
+/// no se toca.
+fn extract_cwd_override(config: &ResolvedConfig, params: &mut Vec<String>) -> Result<PathBuf> {
+    let mut cwd: Option<String> = None;
+    let mut remaining = Vec::new();
+    let passthrough = split_passthrough(params);
+
+    let mut iter = std::mem::take(params).into_iter();
+    while let Some(param) = iter.next() {
+        if let Some(value) = param.strip_prefix("--cwd=") {
+            cwd = Some(value.to_string());
+        } else if param == "--cwd" {
+            cwd = Some(
+                iter.next()
+                    .ok_or_else(|| anyhow!("El flag '--cwd' requiere una ruta."))?,
+            );
+        } else {
+            remaining.push(param);
+        }
+    }
+    remaining.extend(passthrough);
+    *params = remaining;
+
+    let Some(cwd) = cwd else {
+        return Ok(config.project_root.clone());
+    };
+    let workdir = resolve_path_within_root(config, &cwd)?;
+    if !workdir.is_dir() {
+        anyhow::bail!("'--cwd {}' no es un directorio.", cwd);
+    }
+    Ok(workdir)
+}
+
+/// Extrae de los parámetros `--watch`, `--watch-path <ruta>` y `--ignore <glob>` (ambos
+/// repetibles). Devuelve `None` si no se pidió `--watch`. Sin `--watch-path` se observa
+/// la raíz del proyecto; las rutas relativas se toman desde ella. Lo que sigue a `--` no se toca.
+fn extract_watch_options(
+    config: &ResolvedConfig,
+    params: &mut Vec<String>,
+) -> Result<Option<WatchOptions>> {
+    let mut watch = false;
+    let mut options = WatchOptions::default();
+    let mut remaining = Vec::new();
+    let passthrough = split_passthrough(params);
+
+    let mut iter = std::mem::take(params).into_iter();
+    while let Some(param) = iter.next() {
+        match param.as_str() {
+            "--watch" => watch = true,
+            "--watch-path" | "--ignore" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| anyhow!("El flag '{}' requiere un valor.", param))?;
+                if param == "--watch-path" {
+                    options.paths.push(config.project_root.join(value));
+                } else {
+                    options.ignore.push(value);
+                }
+            }
+            _ => remaining.push(param),
+        }
+    }
+    remaining.extend(passthrough);
+    *params = remaining;
+
+    if !watch {
+        if !options.paths.is_empty() || !options.ignore.is_empty() {
+            return Err(anyhow!(
+                "'--watch-path' e '--ignore' solo tienen sentido junto con '--watch'."
+            ));
+        }
+        return Ok(None);
+    }
+    if options.paths.is_empty() {
+        options.paths.push(config.project_root.clone());
+    }
+    Ok(Some(options))
+}
+
+/// Saca de `params` los `--env CLAVE=VALOR` (o `--env=CLAVE=VALOR`), que pueden repetirse.
+/// Los valores se usan tal cual, sin interpolar. Lo que sigue a `--` no se toca.
+fn extract_env_overrides(params: &mut Vec<String>) -> Result<Vec<(String, String)>> {
+    let mut overrides = Vec::new();
+    let mut remaining = Vec::new();
+    let passthrough = split_passthrough(params);
+
+    let mut iter = std::mem::take(params).into_iter();
+    while let Some(param) = iter.next() {
+        let assignment = if let Some(value) = param.strip_prefix("--env=") {
+            value.to_string()
+        } else if param == "--env" {
+            iter.next()
+                .ok_or_else(|| anyhow!("El flag '--env' requiere un valor CLAVE=VALOR."))?
+        } else {
+            remaining.push(param);
+            continue;
+        };
+        match assignment.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                overrides.push((key.to_string(), value.to_string()))
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Valor de '--env' inválido: '{}'. Usa el formato CLAVE=VALOR.",
+                    assignment
+                ));
+            }
+        }
+    }
+    remaining.extend(passthrough);
+    *params = remaining;
+    Ok(overrides)
+}
+
+/// Ejecuta un script en cada descendiente del proyecto que lo tenga (propio o heredado),
+/// en orden de nombre. Los que no lo tienen se omiten con una nota. Un fallo no detiene al
+/// resto; al final se resume qué proyectos fallaron. Con `--parallel`, se ejecutan a la vez
+/// los subárboles independientes (como mucho tantos como CPUs) y cada línea de salida lleva
+/// el nombre del proyecto. Lo que sigue a `--` se pasa tal cual al script.
+fn handle_foreach(config: &ResolvedConfig, mut args: Vec<String>) -> Result<()> {
+    let passthrough = split_passthrough(&mut args);
+    let parallel = args.iter().any(|a| a == "--parallel");
+    args.retain(|a| a != "--parallel");
+    args.extend(passthrough);
+    let mut rest = args.into_iter();
+    let script_key = rest.next().ok_or_else(|| {
+        anyhow!("El comando 'foreach' requiere el nombre de un script (ej: 'foreach build').")
+    })?;
+    let params: Vec<String> = rest.collect();
+
+    let index = index_manager::load_and_ensure_global_project()?;
+    let mut descendants: Vec<(Uuid, String)> =
+        index_manager::get_all_descendants(&index, config.uuid)
+            .into_iter()
+            .filter_map(|uuid| {
+                index_manager::build_qualified_name(uuid, &index)
+                    .ok()
+                    .map(|name| (uuid, name))
+            })
+            .collect();
+    descendants.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+    let mut targets = Vec::new();
+    for (uuid, name) in descendants {
+        let target = config_resolver::resolve_config_for_uuid(uuid, name.clone(), &index)
+            .with_context(|| format!("No se pudo cargar la configuración de '{}'.", name))?;
+        if get_script_runnable(&target, &script_key).is_ok() {
+            targets.push(target);
+        } else {
+            status!(
+                "Nota: '{}' no tiene el script '{}'; se omite.",
+                name,
+                script_key
+            );
+        }
+    }
+    if targets.is_empty() {
+        return Err(anyhow!(
+            "Ningún descendiente de '{}' tiene el script '{}'.",
+            config.qualified_name,
+            script_key
+        ));
+    }
+
+    let failed = if parallel {
+        run_foreach_parallel(&targets, &script_key, &params)?
+    } else {
+        let mut failed = Vec::new();
+        for target in &targets {
+            status!("\n=== {} ===", target.qualified_name);
+            if let Err(e) = handle_run(target, Some(script_key.clone()), params.clone()) {
+                eprintln!(
+                    "{} '{}' falló: {:#}",
+                    output::fail_mark(),
+                    target.qualified_name,
+                    e
+                );
+                failed.push(target.qualified_name.clone());
+            }
+            if axes::system::executor::was_interrupted() {
+                break;
+            }
+        }
+        failed
+    };
+
+    if !failed.is_empty() {
+        println!(
+            "\nResumen: '{}' falló en {} de {} proyectos:",
+            script_key,
+            failed.len(),
+            targets.len()
+        );
+        for name in &failed {
+            println!("  - {}", name);
+        }
+        return Err(anyhow!(
+            "El script '{}' falló en {} proyecto(s).",
+            script_key,
+            failed.len()
+        ));
+    }
+    status!(
+        "\n{} '{}' se ejecutó con éxito en {} proyectos.",
+        output::ok_mark(),
+        script_key,
+        targets.len()
+    );
+    Ok(())
+}
+
+/// Ejecuta `foreach` en paralelo: cada proyecto en un proceso `axes <uuid> run` propio
+/// (como mucho tantos a la vez como CPUs). Un proyecto no empieza mientras se ejecuta uno de
+/// sus ancestros o descendientes. Devuelve los nombres de los que fallaron.
+fn run_foreach_parallel(
+    targets: &[ResolvedConfig],
+    script_key: &str,
+    params: &[String],
+) -> Result<Vec<String>> {
+    let exe = env::current_exe().context("No se pudo localizar el ejecutable de axes.")?;
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(targets.len());
+    // (pendientes, en ejecución). Un trabajador espera mientras todo lo pendiente choca con
+    // lo que está en marcha; siempre hay alguno en marcha que le avisará al terminar.
+    let state = Mutex::new((targets.iter().collect::<Vec<_>>(), Vec::<&str>::new()));
+    let finished = Condvar::new();
+    let failed = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let target = {
+                        let mut guard = state.lock().unwrap();
+                        loop {
+                            let (pending, running) = &mut *guard;
+                            if pending.is_empty() || axes::system::executor::was_interrupted() {
+                                break None;
+                            }
+                            let names: Vec<&str> =
+                                pending.iter().map(|t| t.qualified_name.as_str()).collect();
+                            if let Some(i) = next_independent(&names, running) {
+                                let target = pending.remove(i);
+                                running.push(&target.qualified_name);
+                                break Some(target);
+                            }
+                            guard = finished.wait(guard).unwrap();
+                        }
+                    };
+                    let Some(target) = target else {
+                        break;
+                    };
+
+                    let succeeded = run_prefixed_child(&exe, target, script_key, params)
+                        .unwrap_or_else(|e| {
+                            eprintln!("[{}] {}", target.qualified_name, e);
+                            false
+                        });
+                    if !succeeded {
+                        failed.lock().unwrap().push(target.qualified_name.clone());
+                    }
+                    state
+                        .lock()
+                        .unwrap()
+                        .1
+                        .retain(|name| *name != target.qualified_name);
+                    finished.notify_all();
+                }
+            });
+        }
+    });
+
+    let mut failed = failed.into_inner().unwrap();
+    failed.sort();
+    Ok(failed)
+}
+
+/// El primero de `pending` que no es ancestro ni descendiente de ninguno de `running`
+/// (nombres cualificados).
+fn next_independent(pending: &[&str], running: &[&str]) -> Option<usize> {
+    let nested = |a: &str, b: &str| {
+        let contains = |outer: &str, inner: &str| {
+            inner
+                .strip_prefix(outer)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        };
+        contains(a, b) || contains(b, a)
+    };
+    pending
+        .iter()
+        .position(|name| running.iter().all(|busy| !nested(name, busy)))
+}
+
+/// Lanza `axes <uuid> run <script> --yes [params...]` y reenvía su salida con cada línea
+/// prefijada por el nombre del proyecto. Devuelve si terminó con éxito.
+fn run_prefixed_child(
+    exe: &Path,
+    target: &ResolvedConfig,
+    script_key: &str,
+    params: &[String],
+) -> std::io::Result<bool> {
+    let mut command = std::process::Command::new(exe);
+    if output::is_quiet() {
+        command.arg("--quiet");
+    }
+    let mut child = command
+        .arg(target.uuid.to_string())
+        .arg("run")
+        .arg(script_key)
+        // No se puede responder a una confirmación con la salida entremezclada.
+        .arg("--yes")
+        .args(params)
+        // El hijo recibe el contexto por UUID; en una sesión no debe heredar el de la sesión.
+        .env_remove("AXES_PROJECT_UUID")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    std::thread::scope(|scope| {
+        if let Some(stdout) = stdout {
+            scope.spawn(|| forward_prefixed(stdout, &target.qualified_name, false));
+        }
+        if let Some(stderr) = stderr {
+            scope.spawn(|| forward_prefixed(stderr, &target.qualified_name, true));
+        }
+    });
+
+    Ok(child.wait()?.success())
+}
+
+/// Copia las líneas de `reader` a stdout (o stderr) con el prefijo `[nombre]`.
+fn forward_prefixed(reader: impl Read, prefix: &str, to_stderr: bool) {
+    for line in BufReader::new(reader).lines().map_while(Result::ok) {
+        if to_stderr {
+            eprintln!("[{}] {}", prefix, line);
+        } else {
+            println!("[{}] {}", prefix, line);
+        }
+    }
+}
+
+/// Lista los scripts ejecutables del proyecto, uno por línea (`nombre<TAB>descripción`),
+/// pensada para tuberías (ej. `fzf`). Los multi-plataforma sin versión para este SO se omiten.
+fn handle_scripts(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    if let Some(arg) = args.first() {
+        return Err(anyhow!(
+            "Argumento inesperado '{}' para el comando 'scripts'.",
+            arg
+        ));
+    }
+
+    // En el orden de definición (los heredados primero).
+    for name in config.commands.keys() {
+        let desc = match &config.commands[name] {
+            ProjectCommand::Extended(ext) => ext.desc.as_deref(),
+            ProjectCommand::Platform(pc) => {
+                if get_script_runnable(config, name).is_err() {
+                    continue;
+                }
+                pc.desc.as_deref()
+            }
+            ProjectCommand::Simple(_) | ProjectCommand::Sequence(_) => None,
+        };
+        match desc {
+            Some(desc) => println!("{}\t{}", name, desc),
+            None => println!("{}", name),
+        }
+    }
+
+    Ok(())
+}
+
+/// Ejecuta un comando puntual (no definido en `axes.toml`) en el contexto del proyecto:
+/// en su raíz, con su `[env]` y con las variables de interpolación (`{root}`, etc.).
+/// Los argumentos (tras un `--` opcional) se unen con espacios, como haría un shell.
+fn handle_exec(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let command_args = match args.split_first() {
+        Some((first, rest)) if first == "--" => rest,
+        _ => &args[..],
+    };
+    if command_args.is_empty() {
+        return Err(anyhow!(
+            "El comando 'exec' requiere un comando a ejecutar. Uso: axes <contexto> exec -- <comando>"
+        ));
+    }
+
+    let interpolator = axes::core::interpolator::Interpolator::new(config, &[]);
+    let final_command = interpolator.interpolate(&command_args.join(" "));
+    status!("\n> {}", final_command);
+    axes::system::executor::execute_command(&final_command, &config.project_root, &config.env)
+        .map_err(|e| anyhow!(e))
+}
+
+/// Muestra a qué se resuelve un script sin ejecutarlo: la variante elegida para este SO,
+/// los comandos ya interpolados (con los parámetros dados) y el proyecto que lo define.
+fn handle_which(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let (script_key, params) = args
+        .split_first()
+        .ok_or_else(|| anyhow!("El comando 'which' requiere el nombre de un script."))?;
+    let command_def = config.commands.get(script_key).ok_or_else(|| {
+        anyhow!(
+            "Script '{}' no encontrado en la configuración del proyecto.",
+            script_key
+        )
+    })?;
+
+    let index = index_manager::load_and_ensure_global_project()?;
+    let chain = config_resolver::get_inheritance_chain(config.uuid, &index)?;
+    let sources = config_resolver::trace_value_sources(&chain);
+    let provider = sources
+        .commands
+        .get(script_key)
+        .and_then(|uuid| chain.iter().find(|link| link.uuid == *uuid));
+
+    println!("Script '{}' de '{}'", script_key, config.qualified_name);
+    if let Some(link) = provider {
+        println!(
+            "  Definido en:  {} ({})",
+            link.name,
+            link.config_path.display()
+        );
+    }
+
+    let kind = match command_def {
+        ProjectCommand::Simple(_) => "comando simple".to_string(),
+        ProjectCommand::Sequence(_) => "secuencia".to_string(),
+        ProjectCommand::Extended(_) => "comando extendido".to_string(),
+        ProjectCommand::Platform(pc) => match select_platform_runnable(pc) {
+            Some((variant, _)) => format!("multi-plataforma (variante '{}')", variant),
+            None => "multi-plataforma (sin variante para este SO)".to_string(),
+        },
+    };
+    println!("  Tipo:         {}", kind);
+
+    if let ProjectCommand::Extended(ext) = command_def
+        && !ext.depends_on.is_empty()
+    {
+        println!("  Depende de:   {}", ext.depends_on.join(", "));
+    }
+
+    let runnable = get_script_runnable(config, script_key)?;
+    let interpolator = axes::core::interpolator::Interpolator::new(config, params);
+    let hook_interpolator = axes::core::interpolator::Interpolator::new(config, &[]);
+    let (pre, post) = match command_def {
+        ProjectCommand::Extended(ext) => (ext.pre.as_ref(), ext.post.as_ref()),
+        _ => (None, None),
+    };
+    if let Some(pre) = pre {
+        print_resolved_runnable("pre", pre, &hook_interpolator);
+    }
+    print_resolved_runnable("run", &runnable, &interpolator);
+    if let Some(post) = post {
+        print_resolved_runnable("post", post, &hook_interpolator);
+    }
+
+    Ok(())
+}
+
+/// Imprime los comandos de un `Runnable` ya interpolados.
+fn print_resolved_runnable(
+    label: &str,
+    runnable: &Runnable,
+    interpolator: &axes::core::interpolator::Interpolator,
+) {
+    println!("  {}:", label);
+    match runnable {
+        Runnable::Single(command) => println!("    > {}", interpolator.interpolate(command)),
+        Runnable::Sequence(commands) => {
+            for (i, command) in commands.iter().enumerate() {
+                println!(
+                    "    [{}/{}]> {}",
+                    i + 1,
+                    commands.len(),
+                    interpolator.interpolate(command)
+                );
+            }
+        }
+    }
+}
+
+/// Formatos de salida de `axes <ctx> env`.
+#[derive(Debug, Clone, Copy)]
+enum EnvFormat {
+    Posix,
+    Cmd,
+    PowerShell,
+    Dotenv,
+}
+
+impl EnvFormat {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "posix" | "sh" => Ok(EnvFormat::Posix),
+            "cmd" => Ok(EnvFormat::Cmd),
+            "powershell" | "pwsh" => Ok(EnvFormat::PowerShell),
+            "dotenv" => Ok(EnvFormat::Dotenv),
+            other => Err(anyhow!(
+                "Formato '{}' no soportado. Usa: posix, cmd, powershell o dotenv.",
+                other
+            )),
+        }
+    }
+
+    /// Formato por defecto según el sistema operativo.
+    fn platform_default() -> Self {
+        if cfg!(target_os = "windows") {
+            EnvFormat::Cmd
+        } else {
+            EnvFormat::Posix
+        }
+    }
+
+    fn format_line(self, key: &str, value: &str) -> String {
+        match self {
+            EnvFormat::Posix => format!("export {}='{}'", key, value.replace('\'', "'\\''")),
+            EnvFormat::Cmd => format!("set \"{}={}\"", key, value),
+            EnvFormat::PowerShell => format!("$env:{} = '{}'", key, value.replace('\'', "''")),
+            EnvFormat::Dotenv => format!(
+                "{}=\"{}\"",
+                key,
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            ),
+        }
+    }
+}
+
+/// Imprime el entorno resuelto del proyecto como sentencias listas para cargar en una shell.
+/// Ej: `eval "$(axes mi-app env)"`. Es de solo lectura.
+fn handle_env(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let mut format = EnvFormat::platform_default();
+    let mut args_iter = args.into_iter();
+    while let Some(arg) = args_iter.next() {
+        if let Some(value) = arg.strip_prefix("--format=") {
+            format = EnvFormat::parse(value)?;
+        } else if arg == "--format" {
+            let value = args_iter
+                .next()
+                .ok_or_else(|| anyhow!("'--format' requiere un valor."))?;
+            format = EnvFormat::parse(&value)?;
+        } else {
+            return Err(anyhow!(
+                "Argumento inesperado '{}' para el comando 'env'.",
+                arg
+            ));
+        }
+    }
+
+    let interpolator = axes::core::interpolator::Interpolator::new(config, &[]);
+    let mut keys: Vec<_> = config.env.keys().collect();
+    keys.sort();
+    for key in keys {
+        let value = interpolator.interpolate(&config.env[key]);
+        println!("{}", format.format_line(key, &value));
+    }
+
+    Ok(())
+}
+
+/// La respuesta negativa de `info --has`. No es un fallo, así que `main` no la imprime: solo
+/// sale con su código, distinto del 1 (error de axes) y del 2 (uso incorrecto, de clap).
+#[derive(Debug, thiserror::Error)]
+enum InfoHasAnswer {
+    /// El script existe pero no tiene variante para este SO.
+    #[error("El script '{0}' no está disponible en esta plataforma.")]
+    Unavailable(String),
+    /// El script no está definido.
+    #[error("El script '{0}' no existe.")]
+    Missing(String),
+}
+
+impl InfoHasAnswer {
+    fn exit_code(&self) -> i32 {
+        match self {
+            InfoHasAnswer::Unavailable(_) => 3,
+            InfoHasAnswer::Missing(_) => 4,
+        }
+    }
+}
+
+/// Imprime las `[vars]` fusionadas, ya interpoladas igual que en los scripts, como
+/// `clave=valor` (una por línea) o, con `--json`, como un objeto JSON. Es de solo lectura.
+fn handle_vars(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let mut json = false;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ => {
+                return Err(anyhow!(
+                    "Argumento inesperado '{}' para el comando 'vars'.",
+                    arg
+                ));
+            }
+        }
+    }
+
+    let interpolator = axes::core::interpolator::Interpolator::new(config, &[]);
+    let vars: BTreeMap<&str, String> = config
+        .vars
+        .iter()
+        .map(|(key, value)| (key.as_str(), interpolator.interpolate(value)))
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&vars)?);
+    } else {
+        for (key, value) in vars {
+            println!("{}={}", key, value);
+        }
+    }
+    Ok(())
+}
+
+/// Muestra el UUID, el nombre cualificado y la ruta a los que resuelve un contexto, sin
+/// resolver la configuración del proyecto. Con `--json` los imprime como un objeto JSON.
+fn handle_resolve(
+    uuid: Uuid,
+    qualified_name: &str,
+    index: &GlobalIndex,
+    args: Vec<String>,
+) -> Result<()> {
+    let mut json = false;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ => {
+                return Err(anyhow!(
+                    "Argumento inesperado '{}' para el comando 'resolve'.",
+                    arg
+                ));
+            }
+        }
+    }
+
+    let path = index
+        .projects
+        .get(&uuid)
+        .map(|entry| entry.path.clone())
+        .ok_or_else(|| anyhow!("El proyecto resuelto ({}) no está en el índice.", uuid))?;
+
+    if json {
+        let resolved = serde_json::json!({
+            "uuid": uuid.to_string(),
+            "qualified_name": qualified_name,
+            "path": path,
+        });
+        println!("{}", serde_json::to_string_pretty(&resolved)?);
+    } else {
+        println!("UUID:   {}", uuid);
+        println!("Nombre: {}", qualified_name);
+        println!("Ruta:   {}", path.display());
+    }
+    Ok(())
+}
+
+/// Muestra información detallada sobre la configuración resuelta del proyecto.
+/// Con `--chain` muestra además la cadena de herencia y el origen de cada valor.
+fn handle_info(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let mut show_chain = false;
+    let mut commands_only = false;
+    let mut has_script: Option<String> = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--chain" => show_chain = true,
+            "--commands-only" => commands_only = true,
+            "--has" => {
+                has_script = Some(
+                    args.next()
+                        .ok_or_else(|| anyhow!("'--has' requiere el nombre de un script."))?,
+                );
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Argumento inesperado '{}' para el comando 'info'.",
+                    arg
+                ));
+            }
+        }
+    }
+
+    // `--has` no imprime nada: solo comunica el resultado con el código de salida.
+    if let Some(script) = has_script {
+        match config.commands.get(&script) {
+            Some(command) if is_command_available(command) => return Ok(()),
+            Some(_) => return Err(InfoHasAnswer::Unavailable(script).into()),
+            None => return Err(InfoHasAnswer::Missing(script).into()),
+        }
+    }
+
+    if commands_only {
+        for (cmd_name, command) in &config.commands {
+            if is_command_available(command) {
+                println!("{}", cmd_name);
+            } else {
+                println!("{} (no disponible en esta plataforma)", cmd_name);
+            }
+        }
+        return Ok(());
+    }
+
+    let config_file_path = config
+        .project_root
+        .join(AXES_DIR)
+        .join(PROJECT_CONFIG_FILENAME);
+
     println!("\n--- Información de '{}' ---", config.qualified_name);
     println!("  UUID:           {}", config.uuid);
     println!("  Ruta Raíz:    {}", config.project_root.display());
     println!("  Archivo Conf:   {}", config_file_path.display());
 
+    if let Some(entry) = index_manager::load_and_ensure_global_project()
+        .ok()
+        .and_then(|index| index.projects.get(&config.uuid).cloned())
+    {
+        if let Some(created) = entry.created {
+            println!("  Registrado:     {}", output::format_timestamp(created));
+        }
+        if let Some(modified) = entry.modified {
+            println!("  Modificado:     {}", output::format_timestamp(modified));
+        }
+    }
+
     if let Some(v) = &config.version {
         println!("  Versión:        {}", v);
     }
@@ -459,33 +2003,24 @@ fn handle_info(config: &ResolvedConfig) -> Result<()> {
 
     if !config.commands.is_empty() {
         println!("\n  Comandos Disponibles:");
-        let mut cmd_names: Vec<_> = config.commands.keys().collect();
-        cmd_names.sort();
-        for cmd_name in cmd_names {
-            if let Some(command_def) = config.commands.get(cmd_name) {
-                // **CORRECCIÓN**: El match ahora extrae la struct interna `ext`.
-                match command_def {
-                    ProjectCommand::Sequence(_) => {
-                        println!("    - {} (secuencia de comandos)", cmd_name)
-                    }
-                    ProjectCommand::Extended(ext) => {
-                        if let Some(d) = &ext.desc {
-                            println!("    - {} : {}", cmd_name, d);
-                        } else {
-                            println!("    - {}", cmd_name);
-                        }
-                    }
-                    ProjectCommand::Simple(_) => {
-                        println!("    - {}", cmd_name)
-                    }
-                    ProjectCommand::Platform(pc) => {
-                        if let Some(d) = &pc.desc {
-                            println!("    - {} : {}", cmd_name, d);
-                        } else {
-                            println!("    - {} (multi-plataforma)", cmd_name);
-                        }
-                    }
-                }
+        let origins = command_origins(config);
+        // En el orden de definición (los heredados primero).
+        for (cmd_name, command_def) in &config.commands {
+            let detail = match command_def {
+                ProjectCommand::Sequence(_) => " (secuencia de comandos)".to_string(),
+                ProjectCommand::Extended(ext) => match &ext.desc {
+                    Some(d) => format!(" : {}", d),
+                    None => String::new(),
+                },
+                ProjectCommand::Simple(_) => String::new(),
+                ProjectCommand::Platform(pc) => match &pc.desc {
+                    Some(d) => format!(" : {}", d),
+                    None => " (multi-plataforma)".to_string(),
+                },
+            };
+            match origins.get(cmd_name) {
+                Some(origin) => println!("    - {}{}  [{}]", cmd_name, detail, origin),
+                None => println!("    - {}{}", cmd_name, detail),
             }
         }
     } else {
@@ -494,33 +2029,157 @@ fn handle_info(config: &ResolvedConfig) -> Result<()> {
 
     if !config.vars.is_empty() {
         println!("\n  Variables (fusionadas):");
-        for (key, val) in &config.vars {
+        for (key, val) in config.vars.iter().collect::<BTreeMap<_, _>>() {
             println!("    - {} = \"{}\"", key, val);
         }
     }
 
     if !config.env.is_empty() {
         println!("\n  Variables de Entorno (fusionadas):");
-        for (key, val) in &config.env {
+        for (key, val) in axes::system::executor::sorted_env_vars(&config.env) {
             println!("    - {} = \"{}\"", key, val);
         }
     }
 
+    if show_chain {
+        print_inheritance_chain(config)?;
+    }
+
     println!("\n--------------------------");
     Ok(())
 }
 
-/// Abre el proyecto con una aplicación configurada.
-fn handle_open(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+/// Para cada comando, de dónde sale: `local` si lo define el propio proyecto o
+/// `de <proyecto>` si se hereda. Vacío si no se puede reconstruir la cadena de herencia
+/// (`info` sigue funcionando, solo sin anotar el origen).
+fn command_origins(config: &ResolvedConfig) -> HashMap<String, String> {
+    let chain = match index_manager::load_and_ensure_global_project()
+        .map_err(anyhow::Error::from)
+        .and_then(|index| Ok(config_resolver::get_inheritance_chain(config.uuid, &index)?))
+    {
+        Ok(chain) => chain,
+        Err(e) => {
+            log::debug!("No se pudo reconstruir el origen de los comandos: {}", e);
+            return HashMap::new();
+        }
+    };
+
+    config_resolver::trace_value_sources(&chain)
+        .commands
+        .into_iter()
+        .filter_map(|(name, source_uuid)| {
+            let origin = if source_uuid == config.uuid {
+                "local".to_string()
+            } else {
+                let link = chain.iter().find(|link| link.uuid == source_uuid)?;
+                format!("de {}", link.name)
+            };
+            Some((name, origin))
+        })
+        .collect()
+}
+
+/// Imprime, para cada clave, el nombre del proyecto de la cadena que aporta su valor final.
+fn print_value_sources(
+    title: &str,
+    sources: &HashMap<String, Uuid>,
+    chain: &[config_resolver::ChainLink],
+) {
+    if sources.is_empty() {
+        return;
+    }
+    let mut keys: Vec<&String> = sources.keys().collect();
+    keys.sort();
+
+    println!("\n  {}:", title);
+    for key in keys {
+        let provider = chain
+            .iter()
+            .find(|link| link.uuid == sources[key])
+            .map(|link| link.name.as_str())
+            .unwrap_or("?");
+        println!("    - {}  <-  {}", key, provider);
+    }
+}
+
+/// Imprime la cadena de herencia (raíz → hoja) y qué proyecto aporta el valor final de
+/// cada variable, variable de entorno y comando.
+fn print_inheritance_chain(config: &ResolvedConfig) -> Result<()> {
+    let index = index_manager::load_and_ensure_global_project()?;
+    let chain = config_resolver::get_inheritance_chain(config.uuid, &index)?;
+
+    let arrow = if output::use_color() { "→" } else { "->" };
+    println!("\n  Cadena de Herencia (raíz {} hoja):", arrow);
+    for (i, link) in chain.iter().enumerate() {
+        println!(
+            "    {}. {} ({})",
+            i + 1,
+            link.name,
+            link.config_path.display()
+        );
+    }
+
+    let sources = config_resolver::trace_value_sources(&chain);
+    print_value_sources("Origen de Variables", &sources.vars, &chain);
+    print_value_sources("Origen de Variables de Entorno", &sources.env, &chain);
+    print_value_sources("Origen de Comandos", &sources.commands, &chain);
+
+    Ok(())
+}
+
+/// Ejecuta el comando de `[options] logs` (ej. `tail -f {root}/var/log/app.log`).
+fn handle_logs(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    if let Some(arg) = args.first() {
+        return Err(anyhow!(
+            "Argumento inesperado '{}' para el comando 'logs'.",
+            arg
+        ));
+    }
+    let command_template = config.options.logs.as_deref().ok_or_else(|| {
+        anyhow!(
+            "'{}' no define un comando de logs. Añádelo en `[options]`, ej: logs = \"tail -f {{root}}/var/log/app.log\".",
+            config.qualified_name
+        )
+    })?;
+
+    let final_command =
+        axes::core::interpolator::option_command(config, command_template, &config.project_root);
+    status!("\n> {}", final_command);
+
+    axes::system::executor::execute_command(&final_command, &config.project_root, &config.env)
+        .map_err(|e| anyhow!(e))
+}
+
+/// Abre el proyecto (o un archivo/subdirectorio suyo) con una acción de `[options.open_with]`.
+/// Ej: `axes app open vsc src/main.rs`, donde `{path}` pasa a ser ese archivo.
+/// Con `--list` solo muestra las acciones configuradas, sin ejecutar nada; con `--print`,
+/// el comando final que se lanzaría.
+fn handle_open(config: &ResolvedConfig, mut args: Vec<String>) -> Result<()> {
+    if args.iter().any(|a| a == "--list") {
+        print_open_with_list(config);
+        return Ok(());
+    }
+    if args.first().is_some_and(|a| a == "--reveal") {
+        return reveal_in_file_manager(config, &args[1..]);
+    }
+    let print_only = args.iter().any(|a| a == "--print");
+    args.retain(|a| a != "--print");
+
+    // `with` es opcional: `open with vsc` equivale a `open vsc`.
+    let args = match args.first() {
+        Some(first) if first == "with" => &args[1..],
+        _ => &args[..],
+    };
+    if args.len() > 2 {
+        return Err(anyhow!(
+            "Argumento inesperado '{}' para el comando 'open'.",
+            args[2]
+        ));
+    }
+
     // 1. Determinar la clave de la acción de apertura.
-    let open_key = if !args.is_empty() && args[0] == "with" {
-        // Caso: `axes ... open with vsc`
-        args.get(1) // Tomar el nombre de la app
-            .map(|s| s.as_str())
-            .ok_or_else(|| anyhow!("El comando 'open with' requiere el nombre de una aplicación (ej: 'vsc', 'explorer')."))?
-    } else if !args.is_empty() {
-        // Caso: `axes ... open vsc` (atajo)
-        args[0].as_str()
+    let open_key = if let Some(key) = args.first() {
+        key.as_str()
     } else {
         // Caso: `axes ... open` (usar el default)
         config.options.open_with.get("default")
@@ -543,43 +2202,424 @@ fn handle_open(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
         )
     })?;
 
-    // 3. Interpolar y ejecutar. Por ahora, {root} y {path} son iguales.
-    let interpolator = axes::core::interpolator::Interpolator::new(config, &[]);
-    let final_command = interpolator.interpolate(command_template);
+    // 3. Resolver la ruta a abrir. Sin ruta, {path} es la raíz del proyecto.
+    let target_path = match args.get(1) {
+        Some(relative) => resolve_path_within_root(config, relative)?,
+        None => config.project_root.clone(),
+    };
 
-    println!("\n> {}", final_command);
+    // 4. Interpolar y ejecutar.
+    let final_command =
+        axes::core::interpolator::option_command(config, command_template, &target_path);
+
+    if print_only {
+        println!("{}", final_command);
+        return Ok(());
+    }
+    status!("\n> {}", final_command);
 
     axes::system::executor::execute_command(&final_command, &config.project_root, &config.env)
         .map_err(|e| anyhow!(e))
 }
 
-fn handle_rename(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
-    let new_name = args
-        .first()
-        .ok_or_else(|| anyhow!("El comando 'rename' requiere un nuevo nombre para el proyecto."))?
-        .trim();
+/// El comando del gestor de archivos del sistema para mostrar `path`, sin depender de `open_with`.
+fn reveal_command(path: &Path) -> String {
+    reveal_command_for(path, std::env::consts::OS)
+}
 
-    if new_name.is_empty() {
-        return Err(anyhow!("El nuevo nombre no puede estar vacío."));
+/// Como `reveal_command`, para el SO `os` (un valor de `std::env::consts::OS`).
+fn reveal_command_for(path: &Path, os: &str) -> String {
+    let path = dunce::simplified(path).display();
+    match os {
+        "windows" => format!("explorer \"{}\"", path),
+        "macos" => format!("open -R '{}'", path),
+        _ => format!("xdg-open '{}'", path),
     }
-    // Validar que el nuevo nombre no contenga caracteres de ruta ('/' o '\')
-    if new_name.contains('/') || new_name.contains('\\') {
-        return Err(anyhow!("El nuevo nombre no puede contener '/' o '\\'."));
+}
+
+/// `open --reveal [subruta]`: muestra la raíz del proyecto (o la subruta) en el gestor de
+/// archivos del sistema.
+fn reveal_in_file_manager(config: &ResolvedConfig, args: &[String]) -> Result<()> {
+    if args.len() > 1 {
+        return Err(anyhow!(
+            "Argumento inesperado '{}' para 'open --reveal'.",
+            args[1]
+        ));
+    }
+    let target_path = match args.first() {
+        Some(relative) => resolve_path_within_root(config, relative)?,
+        None => config.project_root.clone(),
+    };
+
+    let command = reveal_command(&target_path);
+    status!("\n> {}", command);
+    match axes::system::executor::execute_command(&command, &config.project_root, &config.env) {
+        // `explorer` devuelve 1 aunque haya abierto la ventana correctamente.
+        Err(ExecutionError::NonZeroExitStatus { code: Some(1), .. })
+            if cfg!(target_os = "windows") =>
+        {
+            Ok(())
+        }
+        result => result.map_err(|e| anyhow!(e)),
+    }
+}
+
+/// Imprime las acciones de `[options.open_with]` resueltas, marcando la de `default`.
+fn print_open_with_list(config: &ResolvedConfig) {
+    let open_with = &config.options.open_with;
+    let default_key = open_with.get("default");
+    let mut keys: Vec<&String> = open_with.keys().filter(|k| *k != "default").collect();
+    keys.sort();
+
+    if keys.is_empty() {
+        println!("No hay acciones de apertura configuradas en [options.open_with].");
+        return;
     }
-    // Validar que no sea un nombre reservado
-    if ["global", ".", "..", "*", "_", "**"].contains(&new_name.to_lowercase().as_str()) {
+
+    println!("Acciones de apertura para '{}':", config.qualified_name);
+    let width = keys.iter().map(|k| k.len()).max().unwrap_or(0);
+    for key in keys {
+        let marker = if default_key == Some(key) {
+            "  (default)"
+        } else {
+            ""
+        };
+        println!(
+            "  {:<width$}  {}{}",
+            key,
+            open_with[key],
+            marker,
+            width = width
+        );
+    }
+    if let Some(target) = default_key
+        && !open_with.contains_key(target)
+    {
+        println!(
+            "\nAdvertencia: 'default' apunta a '{}', que no está definida.",
+            target
+        );
+    }
+}
+
+/// Resumen rápido del estado de un proyecto: caché de configuración, `project_ref.bin`
+/// frente al índice y, si está en un repositorio git, rama y cambios pendientes.
+/// Es de solo lectura.
+fn handle_status(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    if let Some(arg) = args.first() {
         return Err(anyhow!(
-            "El nombre '{}' es reservado y no puede usarse para un proyecto.",
-            new_name
+            "Argumento inesperado '{}' para el comando 'status'.",
+            arg
         ));
     }
+    let index = index_manager::load_and_ensure_global_project()?;
+    let entry = index
+        .projects
+        .get(&config.uuid)
+        .ok_or_else(|| anyhow!("El proyecto '{}' ya no está en el índice.", config.uuid))?;
+
+    println!("\n--- Estado de '{}' ---", config.qualified_name);
+
+    // 1. Caché de configuración (tal como lo encontrará el próximo comando).
+    use config_resolver::ConfigCacheStatus;
+    let cache = match config_resolver::inspect_config_cache(
+        &config.project_root,
+        &config.qualified_name,
+    )? {
+        ConfigCacheStatus::Fresh => "al día".to_string(),
+        ConfigCacheStatus::Missing => "no existe (se creará)".to_string(),
+        ConfigCacheStatus::SchemaMismatch { found } => {
+            format!(
+                "de otra versión de axes (esquema v{}); se regenerará",
+                found
+            )
+        }
+        ConfigCacheStatus::Corrupt(e) => format!("ilegible ({}); se regenerará", e),
+        ConfigCacheStatus::NameMismatch => "de otro nombre cualificado; se regenerará".to_string(),
+        ConfigCacheStatus::DependencyMissing(path) => {
+            format!("'{}' ya no existe; se regenerará", path.display())
+        }
+        ConfigCacheStatus::DependencyModified(path) => {
+            format!("'{}' cambió; se regenerará", path.display())
+        }
+    };
+    println!("  Caché de config: {}", cache);
 
-    println!(
+    // 2. `project_ref.bin` frente a la entrada del índice.
+    let project_ref = match index_manager::read_project_ref(&config.project_root) {
+        Ok(r) if r.self_uuid != config.uuid => {
+            format!("UUID distinto ({}); ejecuta `axes doctor`", r.self_uuid)
+        }
+        Ok(r) if r.parent_uuid != entry.parent || r.name != entry.name => {
+            "nombre o padre desactualizados respecto al índice".to_string()
+        }
+        Ok(_) => "coincide con el índice".to_string(),
+        Err(e) => format!("no se pudo leer ({})", e),
+    };
+    println!("  project_ref.bin: {}", project_ref);
+
+    // 3. Git.
+    let git = |args: &[&str]| -> Option<String> {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&config.project_root)
+            .args(args)
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        output.status.success().then(|| {
+            String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string()
+        })
+    };
+    match git(&["rev-parse", "--is-inside-work-tree"]) {
+        Some(_) => {
+            // `--show-current` no imprime nada con un HEAD desacoplado.
+            let branch = git(&["branch", "--show-current"])
+                .filter(|b| !b.is_empty())
+                .unwrap_or_else(|| "(HEAD desacoplado)".to_string());
+            let changes = git(&["status", "--porcelain"])
+                .map(|out| out.lines().count())
+                .unwrap_or(0);
+            let state = if changes == 0 {
+                "limpio".to_string()
+            } else {
+                format!("{} archivo(s) con cambios", changes)
+            };
+            println!("  Git:             rama {}, {}", branch, state);
+        }
+        None => println!("  Git:             no es un repositorio git"),
+    }
+
+    Ok(())
+}
+
+/// Imprime solo la ruta absoluta del proyecto (o de una subruta dentro de él), sin
+/// decoración, para integrarlo en el shell: `cd "$(axes app path)"`.
+fn handle_path(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let path = match args.first() {
+        Some(relative) => resolve_path_within_root(config, relative)?,
+        None => dunce::simplified(&config.project_root).to_path_buf(),
+    };
+    println!("{}", path.display());
+    Ok(())
+}
+
+/// Resuelve una ruta relativa a la raíz del proyecto, exigiendo que exista y no salga de ella.
+fn resolve_path_within_root(config: &ResolvedConfig, relative: &str) -> Result<PathBuf> {
+    let root = dunce::canonicalize(&config.project_root)?;
+    let candidate = root.join(relative);
+    let resolved = dunce::canonicalize(&candidate)
+        .map_err(|_| anyhow!("La ruta '{}' no existe.", candidate.display()))?;
+    if !resolved.starts_with(&root) {
+        return Err(anyhow!(
+            "La ruta '{}' está fuera de la raíz del proyecto ({}).",
+            relative,
+            root.display()
+        ));
+    }
+    Ok(resolved)
+}
+
+/// Claves de `axes.toml` que `axes <contexto> set` sabe modificar.
+const SETTABLE_KEYS: &[&str] = &["description", "version"];
+
+/// Modifica una clave de `axes.toml` (`set description "..."`, `set version 1.2.0`)
+/// sin tocar el resto del archivo: comentarios y orden se conservan.
+fn handle_set(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let (key, value) = match args.as_slice() {
+        [key, value] => (key.as_str(), value.trim()),
+        _ => anyhow::bail!(
+            "Uso: axes <contexto> set <clave> <valor>. Claves disponibles: {}.",
+            SETTABLE_KEYS.join(", ")
+        ),
+    };
+    if !SETTABLE_KEYS.contains(&key) {
+        anyhow::bail!(
+            "No se puede modificar '{}' con 'set'. Claves disponibles: {}.",
+            key,
+            SETTABLE_KEYS.join(", ")
+        );
+    }
+    if key == "version" && (value.is_empty() || value.contains(char::is_whitespace)) {
+        anyhow::bail!("La versión '{}' no es válida (ej. `1.2.0`).", value);
+    }
+
+    let mut document = ConfigDocument::open(&config.project_root)?;
+    let previous = document.set_root_string(key, value)?;
+    document.save()?;
+
+    match previous {
+        Some(previous) => status!(
+            "{} '{}' de '{}': \"{}\" -> \"{}\"",
+            output::ok_mark(),
+            key,
+            config.qualified_name,
+            previous,
+            value
+        ),
+        None => status!(
+            "{} '{}' de '{}' fijado a \"{}\"",
+            output::ok_mark(),
+            key,
+            config.qualified_name,
+            value
+        ),
+    }
+    Ok(())
+}
+
+/// Añade algo a `axes.toml` desde la línea de comandos. Por ahora solo
+/// `add script <nombre> <comando> [--desc "..."] [--force]`.
+fn handle_add(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let force = args.iter().any(|a| a == "--force");
+    let mut desc: Option<String> = None;
+    if let Some(pos) = args.iter().position(|a| a == "--desc") {
+        desc = Some(
+            args.get(pos + 1)
+                .cloned()
+                .ok_or_else(|| anyhow!("'--desc' requiere una descripción."))?,
+        );
+    }
+    // Los posicionales son todo lo que no es un flag ni el valor de `--desc`.
+    let desc_value_pos = args.iter().position(|a| a == "--desc").map(|pos| pos + 1);
+    let positional: Vec<&str> = args
+        .iter()
+        .enumerate()
+        .filter(|(i, a)| !a.starts_with("--") && Some(*i) != desc_value_pos)
+        .map(|(_, a)| a.as_str())
+        .collect();
+
+    let (name, command) = match positional.as_slice() {
+        ["script", name, command] => (name.trim(), command.trim()),
+        ["script", ..] => anyhow::bail!(
+            "Uso: axes <contexto> add script <nombre> <comando> [--desc \"...\"] [--force]."
+        ),
+        [kind, ..] => anyhow::bail!("No se puede añadir '{}'. Usa: add script.", kind),
+        [] => anyhow::bail!("El comando 'add' requiere qué añadir. Usa: add script."),
+    };
+    if name.is_empty() || name.contains(char::is_whitespace) || name.contains('.') {
+        anyhow::bail!(
+            "El nombre de script '{}' no es válido: no puede estar vacío ni contener espacios o puntos.",
+            name
+        );
+    }
+    if command.is_empty() {
+        anyhow::bail!("El comando del script '{}' no puede estar vacío.", name);
+    }
+
+    let mut document = ConfigDocument::open(&config.project_root)?;
+    if document.has_command(name) && !force {
+        anyhow::bail!(
+            "El script '{}' ya existe en '{}'. Usa `--force` para reemplazarlo.",
+            name,
+            document.path().display()
+        );
+    }
+    document.set_command(name, command, desc.as_deref())?;
+    document.save()?;
+
+    // Los descendientes heredan los scripts, así que sus cachés también quedan obsoletos.
+    let index = index_manager::load_and_ensure_global_project()?;
+    index_manager::invalidate_config_caches(&index, config.uuid);
+
+    status!(
+        "{} Script '{}' añadido a '{}'. Ejecútalo con `axes {} run {}`.",
+        output::ok_mark(),
+        name,
+        config.qualified_name,
+        config.qualified_name,
+        name
+    );
+    Ok(())
+}
+
+/// Quita algo de `axes.toml`. Por ahora solo `remove script <nombre> [--dry-run]`.
+fn handle_remove(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let positional: Vec<&str> = args
+        .iter()
+        .filter(|a| !a.starts_with("--"))
+        .map(String::as_str)
+        .collect();
+    let name = match positional.as_slice() {
+        ["script", name] => *name,
+        ["script", ..] => anyhow::bail!("Uso: axes <contexto> remove script <nombre> [--dry-run]."),
+        [kind, ..] => anyhow::bail!("No se puede quitar '{}'. Usa: remove script.", kind),
+        [] => anyhow::bail!("El comando 'remove' requiere qué quitar. Usa: remove script."),
+    };
+
+    let mut document = ConfigDocument::open(&config.project_root)?;
+    let removed = document.remove_command(name)?;
+
+    if dry_run {
+        println!(
+            "Vista previa (no se ha guardado ningún cambio). Se quitaría de '{}':",
+            document.path().display()
+        );
+        for line in removed.lines() {
+            println!("  {}", line.trim_end());
+        }
+        return Ok(());
+    }
+
+    document.save()?;
+    let index = index_manager::load_and_ensure_global_project()?;
+    index_manager::invalidate_config_caches(&index, config.uuid);
+
+    status!(
+        "{} Script '{}' quitado de '{}'.",
+        output::ok_mark(),
+        name,
+        config.qualified_name
+    );
+    Ok(())
+}
+
+/// Renombra un proyecto. Con `--dry-run` solo muestra cómo cambiarían los nombres
+/// cualificados del proyecto y de sus descendientes, sin guardar nada.
+fn handle_rename(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let new_name = args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .ok_or_else(|| anyhow!("El comando 'rename' requiere un nuevo nombre para el proyecto."))?
+        .trim();
+
+    // Validar que no esté vacío, no contenga separadores de ruta ni sea un nombre reservado
+    index_manager::validate_project_name(new_name)?;
+
+    if dry_run {
+        let index = index_manager::load_and_ensure_global_project()?;
+        let changes =
+            index_manager::preview_rename(&index, config.uuid, new_name).with_context(|| {
+                format!(
+                    "No se podría renombrar el proyecto '{}'.",
+                    config.qualified_name
+                )
+            })?;
+        println!("Vista previa (no se ha guardado ningún cambio):");
+        for (_, old_name, new_name) in &changes {
+            println!("  {}  ->  {}", old_name, new_name);
+        }
+        if changes.len() > 1 {
+            println!(
+                "\n{} descendiente(s) cambiarían también de nombre cualificado.",
+                changes.len() - 1
+            );
+        }
+        return Ok(());
+    }
+
+    status!(
         "Renombrando '{}' a '{}'...",
-        config.qualified_name, new_name
+        config.qualified_name,
+        new_name
     );
 
     // 1. Cargar el índice global para modificarlo (operación crítica)
+    let _lock = index_manager::lock_global_index()?;
     let mut index = index_manager::load_and_ensure_global_project()?;
 
     // 2. Renombrar el proyecto en el índice en memoria (esto incluye la validación de hermanos)
@@ -593,13 +2633,23 @@ fn handle_rename(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
     // 3. Guardar el índice global modificado en disco
     index_manager::save_global_index(&index)
         .context("No se pudo guardar el índice global actualizado.")?;
+    let parent_uuid = index.projects.get(&config.uuid).and_then(|e| e.parent);
+    index_manager::invalidate_child_caches(&index, parent_uuid);
+
+    // 4. Los nombres cualificados del proyecto y de sus descendientes han cambiado:
+    //    sus cachés de configuración ya no sirven.
+    let invalidated = index_manager::invalidate_config_caches(&index, config.uuid);
+    log::debug!(
+        "Cachés de configuración invalidados tras el renombrado: {}",
+        invalidated
+    );
 
-    // 4. Obtener y actualizar la referencia local del proyecto (project_ref.bin)
+    // 5. Obtener y actualizar la referencia local del proyecto (project_ref.bin)
     //    Esta lógica está encapsulada en `get_or_create_project_ref` para auto-reparación.
     let mut project_ref = index_manager::get_or_create_project_ref(&config.project_root, config.uuid, &index)
         .with_context(|| format!("No se pudo obtener o crear la referencia local del proyecto `project_ref.bin` para '{}'.", config.qualified_name))?;
 
-    // 5. Actualizar el nombre en la referencia y guardarla.
+    // 6. Actualizar el nombre en la referencia y guardarla.
     project_ref.name = new_name.to_string();
     if let Err(e) = index_manager::write_project_ref(&config.project_root, &project_ref) {
         eprintln!(
@@ -609,13 +2659,14 @@ fn handle_rename(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
         );
     }
 
-    println!("\n✔ ¡Éxito!");
-    println!(
+    status!("\n{} ¡Éxito!", output::ok_mark());
+    status!(
         "El proyecto '{}' ha sido renombrado a '{}'.",
-        config.qualified_name, new_name
+        config.qualified_name,
+        new_name
     );
-    println!(
-        "Nota: el nombre cualificado completo podría haber cambiado. Los cachés se regenerarán automáticamente en la próxima resolución."
+    status!(
+        "Nota: el nombre cualificado del proyecto y de sus descendientes ha cambiado; sus cachés de configuración se han borrado y se regenerarán en la próxima resolución."
     );
 
     Ok(())
@@ -623,113 +2674,294 @@ fn handle_rename(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
 
 ///Registrar proyecto existente.
 fn handle_unregister(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    unregister_projects(&[config.uuid], &args)
+}
+
+/// Desregistra los proyectos `roots` (y con `--children`, todos sus descendientes).
+fn unregister_projects(roots: &[Uuid], args: &[String]) -> Result<()> {
     let unregister_children = args.iter().any(|arg| arg == "--children");
+    let _lock = index_manager::lock_global_index()?;
     let mut index = index_manager::load_and_ensure_global_project()?;
 
-    let mut uuids_to_unregister = vec![config.uuid];
     if unregister_children {
-        println!(
-            "Recolectando todos los descendientes de '{}'...",
-            config.qualified_name
-        );
-        uuids_to_unregister.extend(index_manager::get_all_descendants(&index, config.uuid));
+        println!("Recolectando todos los descendientes...");
+    }
+    let uuids_to_unregister = collect_bulk_targets(&index, roots, unregister_children);
+    if uuids_to_unregister.is_empty() {
+        return Err(anyhow!("No quedan proyectos registrados que coincidan."));
     }
 
     println!(
         "\nSe desregistrarán las siguientes entradas de `axes` (los archivos no serán modificados):"
     );
-    for uuid in &uuids_to_unregister {
-        if let Some(entry) = index.projects.get(uuid) {
-            println!("  - {} (en {})", entry.name, entry.path.display());
-        }
-    }
+    print_bulk_targets(&index, &uuids_to_unregister);
 
-    if !unregister_children
-        && index
-            .projects
-            .values()
-            .any(|e| e.parent == Some(config.uuid))
+    let removed: HashSet<&Uuid> = uuids_to_unregister.iter().collect();
+    if index
+        .projects
+        .iter()
+        .any(|(uuid, e)| !removed.contains(uuid) && e.parent.is_some_and(|p| removed.contains(&p)))
     {
         println!(
-            "\nNota: los hijos directos de '{}' se convertirán en hijos de 'global'.",
-            config.qualified_name
+            "\nNota: los hijos directos que no se desregistran se convertirán en hijos de 'global'."
         );
     }
 
-    if !Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("¿Continuar?")
-        .default(false)
-        .interact()?
-    {
+    if !prompt::confirm("¿Continuar?", false)? {
         println!("Operación cancelada.");
         return Ok(());
     }
 
     let should_reparent = !unregister_children;
+    let affected_parents =
+        index_manager::parents_affected_by_removal(&index, &uuids_to_unregister, should_reparent);
     let removed_count =
         index_manager::remove_from_index(&mut index, &uuids_to_unregister, should_reparent);
 
     index_manager::save_global_index(&index)?;
+    index_manager::invalidate_child_caches(&index, affected_parents);
 
-    println!("\n✔ ¡Éxito! Se desregistraron {} proyectos.", removed_count);
+    status!(
+        "\n{} ¡Éxito! Se desregistraron {} proyectos.",
+        output::ok_mark(),
+        removed_count
+    );
     Ok(())
 }
 
 /// Elimina un proyecto del índice.
 fn handle_delete(config: &ResolvedConfig, args: Vec<String>) -> Result<()> {
+    delete_projects(&[config.uuid], &args)
+}
+
+/// Archivos de `.axes/` que genera axes (no el usuario). `delete --keep-config` borra solo estos.
+const AXES_GENERATED_FILES: &[&str] = &[
+    CONFIG_CACHE_FILENAME,
+    CHILDREN_CACHE_FILENAME,
+    LAST_USED_CACHE_FILENAME,
+    PROJECT_REF_FILENAME,
+];
+
+/// Borra el directorio `.axes` y desregistra los proyectos `roots` (y con `--children`,
+/// todos sus descendientes). Con `--keep-config` solo borra los archivos generados por axes
+/// y conserva `axes.toml`, para poder volver a registrar el proyecto más tarde.
+fn delete_projects(roots: &[Uuid], args: &[String]) -> Result<()> {
     let delete_children = args.iter().any(|arg| arg == "--children");
+    let keep_config = args.iter().any(|arg| arg == "--keep-config");
+    let _lock = index_manager::lock_global_index()?;
     let mut index = index_manager::load_and_ensure_global_project()?;
 
-    let mut uuids_to_process = vec![config.uuid];
-    if delete_children {
-        uuids_to_process.extend(index_manager::get_all_descendants(&index, config.uuid));
+    let uuids_to_process = collect_bulk_targets(&index, roots, delete_children);
+    if uuids_to_process.is_empty() {
+        return Err(anyhow!("No quedan proyectos registrados que coincidan."));
     }
 
     println!("\n**¡ADVERTENCIA: OPERACIÓN DESTRUCTIVA!**");
-    println!("Se eliminarán los directorios `.axes` Y se desregistrarán los siguientes proyectos:");
+    if keep_config {
+        println!(
+            "Se borrarán de `.axes` los archivos generados ({}), conservando `{}`, Y se desregistrarán los siguientes proyectos:",
+            AXES_GENERATED_FILES.join(", "),
+            PROJECT_CONFIG_FILENAME
+        );
+    } else {
+        println!(
+            "Se eliminarán los directorios `.axes` completos (incluido `{}`) Y se desregistrarán los siguientes proyectos:",
+            PROJECT_CONFIG_FILENAME
+        );
+    }
+    print_bulk_targets(&index, &uuids_to_process);
 
-    let mut paths_to_purge = Vec::new();
-    for uuid in &uuids_to_process {
-        if let Some(entry) = index.projects.get(uuid) {
-            println!("  - {} (en {})", entry.name, entry.path.display());
-            paths_to_purge.push(entry.path.join(AXES_DIR));
+    let paths_to_purge: Vec<PathBuf> = uuids_to_process
+        .iter()
+        .filter_map(|uuid| index.projects.get(uuid))
+        .map(|entry| entry.path.join(AXES_DIR))
+        .collect();
+
+    if !prompt::confirm("¿ESTÁS SEGURO?", false)? {
+        println!("Operación cancelada.");
+        return Ok(());
+    }
+
+    // 1. Purgar archivos (lo hacemos primero, por si falla, no dejamos el índice inconsistente)
+    let mut purged_count = 0;
+    for path in paths_to_purge {
+        if !path.exists() {
+            continue;
+        }
+        let result = if keep_config {
+            remove_generated_files(&path)
+        } else {
+            fs::remove_dir_all(&path)
+        };
+        match result {
+            Ok(()) => purged_count += 1,
+            Err(e) => eprintln!("Advertencia: no se pudo purgar {}: {}", path.display(), e),
+        }
+    }
+
+    // 2. Desregistrar del índice (nunca re-parentamos en un delete recursivo)
+    let affected_parents =
+        index_manager::parents_affected_by_removal(&index, &uuids_to_process, false);
+    let removed_count = index_manager::remove_from_index(&mut index, &uuids_to_process, false);
+
+    index_manager::save_global_index(&index)?;
+    index_manager::invalidate_child_caches(&index, affected_parents);
+
+    status!("\n{} ¡Éxito!", output::ok_mark());
+    if keep_config {
+        status!(
+            "Se limpiaron {} directorios `.axes` (conservando `{}`) y se desregistraron {} proyectos.",
+            purged_count,
+            PROJECT_CONFIG_FILENAME,
+            removed_count
+        );
+    } else {
+        status!(
+            "Se eliminaron {} directorios `.axes` y se desregistraron {} proyectos.",
+            purged_count,
+            removed_count
+        );
+    }
+    Ok(())
+}
+
+/// Borra de `axes_dir` los archivos de `AXES_GENERATED_FILES` que existan.
+fn remove_generated_files(axes_dir: &Path) -> std::io::Result<()> {
+    for file_name in AXES_GENERATED_FILES {
+        match fs::remove_file(axes_dir.join(file_name)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
         }
     }
+    Ok(())
+}
+
+/// Resuelve un patrón glob de nombres cualificados a los proyectos que coinciden (nunca `global`).
+fn resolve_bulk_targets(pattern: &str) -> Result<Vec<Uuid>> {
+    let index = index_manager::load_and_ensure_global_project()?;
+    let roots: Vec<Uuid> = index_manager::find_projects_by_pattern(&index, pattern, false)?
+        .into_iter()
+        .map(|(uuid, _)| uuid)
+        .filter(|uuid| *uuid != index_manager::GLOBAL_PROJECT_UUID)
+        .collect();
+    if roots.is_empty() {
+        return Err(anyhow!(
+            "Ningún proyecto coincide con el patrón '{}'.",
+            pattern
+        ));
+    }
+    Ok(roots)
+}
+
+/// Une los proyectos `roots` y (con `with_children`) sus descendientes, sin repetir los que
+/// aparecen en subárboles solapados (ej. `a` y `a/b` seleccionados a la vez).
+fn collect_bulk_targets(index: &GlobalIndex, roots: &[Uuid], with_children: bool) -> Vec<Uuid> {
+    let mut seen = HashSet::new();
+    let mut targets = Vec::new();
+    for root in roots {
+        if !index.projects.contains_key(root) {
+            continue;
+        }
+        let descendants = if with_children {
+            index_manager::get_all_descendants(index, *root)
+        } else {
+            Vec::new()
+        };
+        for uuid in std::iter::once(*root).chain(descendants) {
+            if seen.insert(uuid) {
+                targets.push(uuid);
+            }
+        }
+    }
+    targets
+}
+
+/// Desregistra los proyectos cuya ruta ya no existe en el disco. Sus hijos pasan a
+/// `global`, o con `--children` se desregistran también. Con `--dry-run` solo los lista.
+/// Nunca toca `global`.
+fn handle_prune(first_arg: Option<String>, args: Vec<String>) -> Result<()> {
+    let args: Vec<String> = first_arg.into_iter().chain(args).collect();
+    let mut dry_run = false;
+    let mut with_children = false;
+    for arg in &args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--children" => with_children = true,
+            _ => {
+                return Err(anyhow!(
+                    "Argumento inesperado '{}' para el comando 'prune'.",
+                    arg
+                ));
+            }
+        }
+    }
+
+    let _lock = index_manager::lock_global_index()?;
+    let mut index = index_manager::load_and_ensure_global_project()?;
 
-    if !Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("¿ESTÁS SEGURO?")
-        .default(false)
-        .interact()?
-    {
-        println!("Operación cancelada.");
+    let dead: Vec<Uuid> = index_manager::find_missing_project_paths(&index)
+        .into_iter()
+        .map(|(uuid, _)| uuid)
+        .filter(|uuid| *uuid != index_manager::GLOBAL_PROJECT_UUID)
+        .collect();
+    let targets: Vec<Uuid> = collect_bulk_targets(&index, &dead, with_children)
+        .into_iter()
+        .filter(|uuid| *uuid != index_manager::GLOBAL_PROJECT_UUID)
+        .collect();
+    if targets.is_empty() {
+        println!("{} Todas las rutas registradas existen.", output::ok_mark());
         return Ok(());
     }
 
-    // 1. Purgar archivos (lo hacemos primero, por si falla, no dejamos el índice inconsistente)
-    let mut purged_count = 0;
-    for path in paths_to_purge {
-        if path.exists() {
-            if fs::remove_dir_all(&path).is_ok() {
-                purged_count += 1;
-            } else {
-                eprintln!("Advertencia: no se pudo purgar {}", path.display());
-            }
-        }
+    println!("Proyectos cuya ruta ya no existe:");
+    print_bulk_targets(&index, &dead);
+    if targets.len() > dead.len() {
+        println!("\nCon `--children` se desregistrarán también sus descendientes:");
+        let descendants: Vec<Uuid> = targets
+            .iter()
+            .filter(|uuid| !dead.contains(uuid))
+            .copied()
+            .collect();
+        print_bulk_targets(&index, &descendants);
+    }
+    if dry_run {
+        println!("\nVista previa: no se ha desregistrado nada.");
+        return Ok(());
+    }
+    if !with_children {
+        println!("\nNota: sus hijos directos (si los hay) se convertirán en hijos de 'global'.");
     }
 
-    // 2. Desregistrar del índice (nunca re-parentamos en un delete recursivo)
-    let removed_count = index_manager::remove_from_index(&mut index, &uuids_to_process, false);
+    if !prompt::confirm("¿Desregistrarlos?", false)? {
+        println!("Operación cancelada.");
+        return Ok(());
+    }
 
+    let affected_parents =
+        index_manager::parents_affected_by_removal(&index, &targets, !with_children);
+    let removed_count = index_manager::remove_from_index(&mut index, &targets, !with_children);
     index_manager::save_global_index(&index)?;
+    index_manager::invalidate_child_caches(&index, affected_parents);
 
-    println!("\n✔ ¡Éxito!");
-    println!(
-        "Se eliminaron {} directorios `.axes` y se desregistraron {} proyectos.",
-        purged_count, removed_count
+    status!(
+        "\n{} Se desregistraron {} proyectos.",
+        output::ok_mark(),
+        removed_count
     );
     Ok(())
 }
 
+/// Imprime la lista de proyectos afectados por `unregister`/`delete`.
+fn print_bulk_targets(index: &GlobalIndex, uuids: &[Uuid]) {
+    for uuid in uuids {
+        if let Some(entry) = index.projects.get(uuid) {
+            let name = index_manager::build_qualified_name(*uuid, index)
+                .unwrap_or_else(|_| entry.name.clone());
+            println!("  - {} (en {})", name, entry.path.display());
+        }
+    }
+}
+
 /// Registra un proyecto existente en el directorio actual o en una ruta especificada.
 fn handle_register(path_arg: Option<String>, args: Vec<String>) -> Result<()> {
     // 1. Determinar la ruta y los flags de forma robusta.
@@ -738,6 +2970,8 @@ fn handle_register(path_arg: Option<String>, args: Vec<String>) -> Result<()> {
 
     let mut path_to_register = PathBuf::from("."); // Por defecto, el directorio actual
     let mut autosolve = false;
+    let mut recursive = false;
+    let mut max_depth: Option<usize> = None;
 
     // Juntar todos los posibles argumentos en una sola lista para el parseo.
     let mut all_args = Vec::new();
@@ -746,11 +2980,25 @@ fn handle_register(path_arg: Option<String>, args: Vec<String>) -> Result<()> {
     }
     all_args.extend(args);
 
-    // Iterar para encontrar la ruta y el flag.
+    // Iterar para encontrar la ruta y los flags.
     let mut path_found = false;
-    for arg in all_args {
+    let mut args_iter = all_args.into_iter();
+    while let Some(arg) = args_iter.next() {
         if arg == "--autosolve" {
             autosolve = true;
+        } else if arg == "--recursive" {
+            recursive = true;
+        } else if arg == "--depth" {
+            // `--depth` implica `--recursive`.
+            let value = args_iter
+                .next()
+                .ok_or_else(|| anyhow!("'--depth' requiere un número."))?;
+            max_depth = Some(
+                value
+                    .parse()
+                    .map_err(|_| anyhow!("Profundidad inválida para '--depth': '{}'.", value))?,
+            );
+            recursive = true;
         } else if !path_found {
             // El primer argumento que no es un flag es la ruta.
             path_to_register = PathBuf::from(arg);
@@ -772,8 +3020,36 @@ fn handle_register(path_arg: Option<String>, args: Vec<String>) -> Result<()> {
     }
 
     // 2. Cargar el índice
+    let _lock = index_manager::lock_global_index()?;
     let mut index = index_manager::load_and_ensure_global_project()?;
 
+    if recursive {
+        let summary = onboarding_manager::register_projects_recursive(
+            &path_to_register,
+            max_depth,
+            &mut index,
+            autosolve,
+        )?;
+        index_manager::save_global_index(&index)?;
+
+        println!(
+            "\nRegistro recursivo finalizado: {} añadido(s), {} omitido(s) (ya registrados), {} con errores.",
+            summary.added.len(),
+            summary.skipped.len(),
+            summary.failed.len()
+        );
+        for path in &summary.added {
+            println!("  + {}", path.display());
+        }
+        for path in &summary.skipped {
+            println!("  = {}", path.display());
+        }
+        for (path, error) in &summary.failed {
+            println!("  {} {}: {}", output::fail_mark(), path.display(), error);
+        }
+        return Ok(());
+    }
+
     // 3. Configurar opciones y llamar a la máquina de estados
     let options = OnboardingOptions {
         autosolve,
@@ -790,27 +3066,294 @@ fn handle_register(path_arg: Option<String>, args: Vec<String>) -> Result<()> {
     // 4. Guardar los cambios
     index_manager::save_global_index(&index)?;
 
-    println!("\nOperación de registro finalizada.");
+    status!("\nOperación de registro finalizada.");
     Ok(())
 }
 
-fn handle_tree(config: Option<ResolvedConfig>) -> Result<()> {
+/// Muestra el árbol de proyectos. Acepta un patrón glob opcional (y `--ignore-case`) que
+/// poda el árbol a las coincidencias y sus ancestros.
+fn handle_tree(config: Option<ResolvedConfig>, args: Vec<String>) -> Result<()> {
+    let mut format = graph_display::TreeFormat::default();
+    let mut json = false;
+    let mut pattern_args = Vec::new();
+    let mut args_iter = args.into_iter();
+    while let Some(arg) = args_iter.next() {
+        if arg == "--json" {
+            json = true;
+            continue;
+        }
+        let value = if let Some(value) = arg.strip_prefix("--format=") {
+            value.to_string()
+        } else if arg == "--format" {
+            args_iter
+                .next()
+                .ok_or_else(|| anyhow!("'--format' requiere un valor."))?
+        } else {
+            pattern_args.push(arg);
+            continue;
+        };
+        format = graph_display::TreeFormat::parse(&value)
+            .ok_or_else(|| anyhow!("Formato '{}' no soportado. Usa: ascii, md o dot.", value))?;
+    }
+    let (pattern, ignore_case) = parse_pattern_args("tree", pattern_args)?;
     let index = index_manager::load_and_ensure_global_project()?;
+
+    let visible = match &pattern {
+        Some(p) => {
+            let matches = index_manager::find_projects_by_pattern(&index, p, ignore_case)?;
+            if matches.is_empty() {
+                println!("\nNingún proyecto coincide con el patrón '{}'.", p);
+                return Ok(());
+            }
+            Some(graph_display::with_ancestors(
+                &index,
+                matches.into_iter().map(|(uuid, _)| uuid),
+            ))
+        }
+        None => None,
+    };
+
+    if json {
+        let start_node = config
+            .as_ref()
+            .map(|conf| conf.uuid)
+            .filter(|uuid| *uuid != index_manager::GLOBAL_PROJECT_UUID);
+        let tree = graph_display::project_tree_json(&index, start_node, visible.as_ref());
+        println!("{}", serde_json::to_string_pretty(&tree)?);
+        return Ok(());
+    }
+
     match config {
         Some(conf) => {
-            println!("\nMostrando árbol desde: '{}'", conf.qualified_name);
+            if format == graph_display::TreeFormat::Ascii {
+                status!("\nMostrando árbol desde: '{}'", conf.qualified_name);
+            }
             let start_node = if conf.uuid == index_manager::GLOBAL_PROJECT_UUID {
                 None
             } else {
                 Some(conf.uuid)
             };
-            graph_display::display_project_tree(&index, start_node);
+            graph_display::display_project_tree(&index, start_node, visible.as_ref(), format);
         }
         None => {
             // Caso Global
-            graph_display::display_project_tree(&index, None);
+            graph_display::display_project_tree(&index, None, visible.as_ref(), format);
+        }
+    }
+    Ok(())
+}
+
+/// Lista de forma plana los proyectos cuyo nombre cualificado coincide con un patrón glob.
+fn handle_list(pattern_arg: Option<String>, args: Vec<String>) -> Result<()> {
+    let mut all_args = Vec::new();
+    if let Some(p) = pattern_arg {
+        all_args.push(p);
+    }
+    all_args.extend(args);
+    let (pattern, ignore_case) = parse_pattern_args("list", all_args)?;
+    let pattern = pattern.unwrap_or_else(|| "**".to_string());
+
+    let index = index_manager::load_and_ensure_global_project()?;
+    let matches = index_manager::find_projects_by_pattern(&index, &pattern, ignore_case)?;
+
+    if matches.is_empty() {
+        println!("Ningún proyecto coincide con el patrón '{}'.", pattern);
+        return Ok(());
+    }
+
+    for (uuid, qualified_name) in matches {
+        if let Some(entry) = index.projects.get(&uuid) {
+            println!("  {}  [{}]", qualified_name, entry.path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Extrae un patrón posicional opcional y el flag `--ignore-case` de los argumentos.
+fn parse_pattern_args(action: &str, args: Vec<String>) -> Result<(Option<String>, bool)> {
+    let mut pattern = None;
+    let mut ignore_case = false;
+    for arg in args {
+        if arg == "--ignore-case" {
+            ignore_case = true;
+        } else if pattern.is_none() {
+            pattern = Some(arg);
+        } else {
+            return Err(anyhow!(
+                "Argumento inesperado '{}' para el comando '{}'.",
+                arg,
+                action
+            ));
+        }
+    }
+    Ok((pattern, ignore_case))
+}
+
+/// Indica si un contexto es en realidad un patrón glob de nombres (ej. `api-*` o `web/**`)
+/// y no una ruta de navegación. `*` es siempre un token de navegación válido y `**` solo
+/// lo es como primer segmento.
+fn looks_like_name_pattern(context: &str) -> bool {
+    context
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .enumerate()
+        .any(|(i, segment)| {
+            let is_navigation_token = segment == "*" || (i == 0 && segment == "**");
+            !is_navigation_token && segment.contains(['*', '?', '['])
+        })
+}
+
+/// Recoge los `--var clave=valor` de `init` (pueden repetirse) para las plantillas.
+fn parse_template_vars(args: &[String]) -> Result<Vec<(String, String)>> {
+    let mut vars = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg != "--var" {
+            continue;
+        }
+        let assignment = iter
+            .next()
+            .ok_or_else(|| anyhow!("El flag '--var' requiere un valor clave=valor."))?;
+        match assignment.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                vars.push((key.to_string(), value.to_string()))
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Valor de '--var' inválido: '{}'. Usa el formato clave=valor.",
+                    assignment
+                ));
+            }
+        }
+    }
+    Ok(vars)
+}
+
+/// Gestiona las plantillas de proyecto. Por ahora solo `axes templates [list]`.
+fn handle_templates(subcommand: Option<String>, args: Vec<String>) -> Result<()> {
+    if let Some(arg) = args.first() {
+        return Err(anyhow!(
+            "Argumento inesperado '{}' para el comando 'templates'.",
+            arg
+        ));
+    }
+    match subcommand.as_deref().unwrap_or("list") {
+        "list" => print_templates(),
+        other => Err(anyhow!(
+            "Subcomando '{}' no reconocido para 'templates'. Usa: list.",
+            other
+        )),
+    }
+}
+
+/// Lista las plantillas disponibles indicando si vienen incluidas o del directorio del usuario.
+fn print_templates() -> Result<()> {
+    let user_dir = axes::core::paths::get_user_templates_dir()?;
+    println!("Plantillas disponibles:");
+    for (name, origin) in templates::list_templates()? {
+        let origin = match origin {
+            templates::TemplateOrigin::Builtin => "incluida",
+            templates::TemplateOrigin::User => "usuario",
+        };
+        println!("  - {} ({})", name, origin);
+    }
+    println!(
+        "\nPuedes añadir las tuyas como subdirectorios de '{}'.",
+        user_dir.display()
+    );
+    Ok(())
+}
+
+/// Ejecuta el comando de `[options] self_update` del proyecto `global`, para que cada equipo
+/// decida cómo se actualiza axes (cargo, un gestor de paquetes, un script propio...).
+fn handle_self_update(first_arg: Option<String>) -> Result<()> {
+    if let Some(arg) = first_arg {
+        return Err(anyhow!(
+            "Argumento inesperado '{}' para el comando 'self-update'.",
+            arg
+        ));
+    }
+    let index = index_manager::load_and_ensure_global_project()?;
+    let config = config_resolver::resolve_config_for_uuid(
+        index_manager::GLOBAL_PROJECT_UUID,
+        "global".to_string(),
+        &index,
+    )?;
+
+    let Some(command_template) = config.options.self_update.as_deref() else {
+        println!("No hay un comando de actualización configurado.");
+        println!(
+            "Añádelo en `[options]` de '{}', ej: self_update = \"cargo install axes --force\".",
+            config
+                .project_root
+                .join(AXES_DIR)
+                .join(PROJECT_CONFIG_FILENAME)
+                .display()
+        );
+        return Ok(());
+    };
+
+    let final_command =
+        axes::core::interpolator::option_command(&config, command_template, &config.project_root);
+    status!("\n> {}", final_command);
+
+    axes::system::executor::execute_command(&final_command, &config.project_root, &config.env)
+        .map_err(|e| anyhow!(e))
+}
+
+/// Muestra la versión de axes y el entorno que usa (útil para reportar problemas).
+/// Es de solo lectura: no crea el índice si no existe.
+fn handle_version() -> Result<()> {
+    println!("axes {}", env!("CARGO_PKG_VERSION"));
+
+    let config_dir = match axes::core::paths::get_axes_config_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            println!("Directorio de configuración: no disponible ({})", e);
+            return Ok(());
+        }
+    };
+    println!("Directorio de configuración: {}", config_dir.display());
+
+    let index_path = axes::core::paths::get_global_index_path()?;
+    if !index_path.exists() {
+        println!(
+            "Índice global:               {} (todavía no existe)",
+            index_path.display()
+        );
+        println!("Proyectos registrados:       0");
+        return Ok(());
+    }
+    println!("Índice global:               {}", index_path.display());
+
+    let index = match index_manager::read_global_index() {
+        Ok(index) => index,
+        Err(e) => {
+            println!(
+                "Proyectos registrados:       desconocido (no se pudo leer el índice: {})",
+                e
+            );
+            return Ok(());
         }
+    };
+    let registered = index
+        .projects
+        .keys()
+        .filter(|uuid| **uuid != index_manager::GLOBAL_PROJECT_UUID)
+        .count();
+    println!("Proyectos registrados:       {}", registered);
+
+    if let Ok(session_uuid) = env::var("AXES_PROJECT_UUID") {
+        let session_name = Uuid::parse_str(&session_uuid)
+            .ok()
+            .and_then(|uuid| index_manager::build_qualified_name(uuid, &index).ok())
+            .unwrap_or_else(|| "<no encontrado en el índice>".to_string());
+        println!(
+            "Proyecto de la sesión:       {} ({})",
+            session_name, session_uuid
+        );
     }
+
     Ok(())
 }
 
@@ -819,6 +3362,7 @@ fn handle_alias(subcommand: Option<String>, args: Vec<String>) -> Result<()> {
     // Si no hay subcomando, el default es `list`.
     let subcommand = subcommand.as_deref().unwrap_or("list");
 
+    let _lock = index_manager::lock_global_index()?;
     let mut index = index_manager::load_and_ensure_global_project()?;
 
     match subcommand {
@@ -846,8 +3390,10 @@ fn handle_alias(subcommand: Option<String>, args: Vec<String>) -> Result<()> {
             index_manager::save_global_index(&index)?;
 
             println!(
-                "✔ Alias '{}!' establecido para apuntar a '{}'.",
-                clean_alias_name, target_name
+                "{} Alias '{}!' establecido para apuntar a '{}'.",
+                output::ok_mark(),
+                clean_alias_name,
+                target_name
             );
         }
         "list" | "ls" => {
@@ -865,7 +3411,7 @@ fn handle_alias(subcommand: Option<String>, args: Vec<String>) -> Result<()> {
 
             for (name, uuid) in sorted_aliases {
                 let target_name = index_manager::build_qualified_name(*uuid, &index)
-                    .unwrap_or_else(|| "<enlace roto>".to_string());
+                    .unwrap_or_else(|_| "<enlace roto>".to_string());
                 println!("  {}!  ->  {}", name, target_name);
             }
         }
@@ -877,7 +3423,11 @@ fn handle_alias(subcommand: Option<String>, args: Vec<String>) -> Result<()> {
 
             if index_manager::remove_alias(&mut index, clean_alias_name) {
                 index_manager::save_global_index(&index)?;
-                println!("✔ Alias '{}!' eliminado.", clean_alias_name);
+                status!(
+                    "{} Alias '{}!' eliminado.",
+                    output::ok_mark(),
+                    clean_alias_name
+                );
             } else {
                 return Err(anyhow!(
                     "El alias '{}!' no fue encontrado o no se puede eliminar.",
@@ -894,3 +3444,420 @@ fn handle_alias(subcommand: Option<String>, args: Vec<String>) -> Result<()> {
 
     Ok(())
 }
+
+/// Diagnostica el índice global: padres rotos, rutas inexistentes y ciclos.
+/// Por defecto solo informa; con `--fix` aplica las reparaciones sugeridas.
+fn handle_doctor(first_arg: Option<String>, args: Vec<String>) -> Result<()> {
+    let mut all_args = Vec::new();
+    if let Some(a) = first_arg {
+        all_args.push(a);
+    }
+    all_args.extend(args);
+
+    let mut fix = false;
+    for arg in all_args {
+        match arg.as_str() {
+            "--fix" => fix = true,
+            _ => {
+                return Err(anyhow!(
+                    "Argumento inesperado '{}' para el comando 'doctor'.",
+                    arg
+                ));
+            }
+        }
+    }
+
+    let _lock = index_manager::lock_global_index()?;
+    let mut index = index_manager::load_and_ensure_global_project()?;
+
+    println!(
+        "\nAnalizando el índice global ({} proyectos)...",
+        index.projects.len()
+    );
+    let issues = index_manager::diagnose_index(&index);
+    let has_unknown_keys = print_unknown_config_keys(&index);
+
+    if issues.is_empty() {
+        if !has_unknown_keys {
+            println!("\n{} No se encontraron problemas.", output::ok_mark());
+        }
+        return Ok(());
+    }
+
+    println!("\nSe encontraron {} problemas:", issues.len());
+    for issue in &issues {
+        let name_of = |uuid: &Uuid| {
+            index
+                .projects
+                .get(uuid)
+                .map(|e| e.name.clone())
+                .unwrap_or_else(|| uuid.to_string())
+        };
+        match issue {
+            IndexIssue::BrokenParentLink {
+                uuid,
+                missing_parent_uuid,
+            } => {
+                println!(
+                    "  - '{}' apunta a un padre inexistente ({}).",
+                    name_of(uuid),
+                    missing_parent_uuid
+                );
+                println!("      Sugerencia: re-parentar a 'global'.");
+            }
+            IndexIssue::MissingPath { uuid, path } => {
+                println!(
+                    "  - '{}' apunta a una ruta que ya no existe: {}",
+                    name_of(uuid),
+                    path.display()
+                );
+                println!(
+                    "      Sugerencia: reubicarlo o desregistrarlo (sus hijos pasarán a 'global')."
+                );
+            }
+            IndexIssue::Cycle {
+                uuid,
+                cycle_node_uuid,
+            } => {
+                println!(
+                    "  - La cadena de padres de '{}' contiene un ciclo en '{}'.",
+                    name_of(uuid),
+                    name_of(cycle_node_uuid)
+                );
+                println!(
+                    "      Sugerencia: re-parentar '{}' a 'global'.",
+                    name_of(cycle_node_uuid)
+                );
+            }
+        }
+    }
+
+    if !fix {
+        println!("\nEjecuta `axes doctor --fix` para aplicar las reparaciones sugeridas.");
+        return Ok(());
+    }
+
+    // Las rutas inexistentes pasan por el flujo interactivo de reubicación; el resto se
+    // repara automáticamente.
+    let mut repaired_count = 0;
+    let mut missing_paths = Vec::new();
+    for issue in &issues {
+        if let IndexIssue::MissingPath { uuid, path } = issue {
+            missing_paths.push((*uuid, path.clone()));
+        } else if index_manager::repair_index_issue(&mut index, issue) {
+            repaired_count += 1;
+        }
+    }
+    repaired_count += repair_missing_paths_interactive(&mut index, &missing_paths)?;
+
+    index_manager::save_global_index(&index)
+        .context("No se pudo guardar el índice global reparado.")?;
+
+    // Mantener los `project_ref.bin` locales coherentes con los nuevos padres.
+    for issue in &issues {
+        let reparented_uuid = match issue {
+            IndexIssue::BrokenParentLink { uuid, .. } => *uuid,
+            IndexIssue::Cycle {
+                cycle_node_uuid, ..
+            } => *cycle_node_uuid,
+            IndexIssue::MissingPath { .. } => continue,
+        };
+        if let Some(entry) = index.projects.get(&reparented_uuid)
+            && entry.path.exists()
+        {
+            let project_ref = ProjectRef {
+                self_uuid: reparented_uuid,
+                parent_uuid: entry.parent,
+                name: entry.name.clone(),
+            };
+            if let Err(e) = index_manager::write_project_ref(&entry.path, &project_ref) {
+                eprintln!(
+                    "Advertencia: no se pudo actualizar `project_ref.bin` en '{}': {}",
+                    entry.path.display(),
+                    e
+                );
+            }
+        }
+    }
+
+    status!(
+        "\n{} ¡Éxito! Se aplicaron {} reparaciones.",
+        output::ok_mark(),
+        repaired_count
+    );
+    Ok(())
+}
+
+/// Lista los `axes.toml` con claves desconocidas (ej. `[comands]`), que se ignoran sin más
+/// aviso. No se reparan solas: hay que corregir la errata a mano. Devuelve si encontró alguna.
+fn print_unknown_config_keys(index: &GlobalIndex) -> bool {
+    let mut entries: Vec<_> = index.projects.iter().collect();
+    entries.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+
+    let mut found = false;
+    for (uuid, entry) in entries {
+        // Los proyectos sin `axes.toml` legible ya salen como ruta inexistente.
+        let Ok(keys) = config_resolver::unknown_config_keys(entry) else {
+            continue;
+        };
+        if keys.is_empty() {
+            continue;
+        }
+        if !found {
+            println!("\nClaves desconocidas en `axes.toml` (se ignoran):");
+            found = true;
+        }
+        let name = index_manager::build_qualified_name(*uuid, index)
+            .unwrap_or_else(|_| entry.name.clone());
+        println!("  - '{}': {}", name, keys.join(", "));
+    }
+    found
+}
+
+/// Comprueba que las rutas de todos los proyectos registrados siguen existiendo.
+fn handle_validate() -> Result<()> {
+    let _lock = index_manager::lock_global_index()?;
+    let mut index = index_manager::load_and_ensure_global_project()?;
+
+    let missing_paths = index_manager::find_missing_project_paths(&index);
+    if missing_paths.is_empty() {
+        log::debug!("Todas las rutas de proyectos registrados son válidas.");
+        return Ok(());
+    }
+
+    println!(
+        "\nSe encontraron {} proyectos cuya ruta ya no existe.",
+        missing_paths.len()
+    );
+    if repair_missing_paths_interactive(&mut index, &missing_paths)? > 0 {
+        index_manager::save_global_index(&index)
+            .context("No se pudo guardar el índice global actualizado.")?;
+    }
+    Ok(())
+}
+
+/// Recorre los proyectos cuya ruta ya no existe y ofrece reubicarlos o desregistrarlos.
+/// Devuelve el número de proyectos reparados.
+fn repair_missing_paths_interactive(
+    index: &mut GlobalIndex,
+    missing_paths: &[(Uuid, PathBuf)],
+) -> Result<usize> {
+    const CHOICES: &[&str] = &[
+        "Reubicar (indicar la nueva ruta)",
+        "Desregistrar del índice",
+        "Omitir",
+    ];
+
+    let mut repaired_count = 0;
+    for (uuid, old_path) in missing_paths {
+        let name =
+            index_manager::build_qualified_name(*uuid, index).unwrap_or_else(|_| uuid.to_string());
+        println!(
+            "\nEl proyecto '{}' apunta a una ruta que ya no existe: {}",
+            name,
+            old_path.display()
+        );
+
+        prompt::require_interactive(&format!("reparación de la ruta de '{}'", name))?;
+        let selection = Select::with_theme(&*output::theme())
+            .with_prompt("¿Qué deseas hacer?")
+            .items(CHOICES)
+            .default(0)
+            .interact()?;
+
+        match selection {
+            0 => loop {
+                let input: String = Input::with_theme(&*output::theme())
+                    .with_prompt("Nueva ruta del proyecto (vacío para omitir)")
+                    .allow_empty(true)
+                    .interact_text()?;
+                if input.trim().is_empty() {
+                    println!("Omitido.");
+                    break;
+                }
+
+                match index_manager::relocate_project(index, *uuid, Path::new(input.trim())) {
+                    Ok(new_path) => {
+                        if let Some(entry) = index.projects.get(uuid) {
+                            let project_ref = ProjectRef {
+                                self_uuid: *uuid,
+                                parent_uuid: entry.parent,
+                                name: entry.name.clone(),
+                            };
+                            if let Err(e) =
+                                index_manager::write_project_ref(&new_path, &project_ref)
+                            {
+                                eprintln!(
+                                    "Advertencia: no se pudo escribir `project_ref.bin` en '{}': {}",
+                                    new_path.display(),
+                                    e
+                                );
+                            }
+                        }
+                        status!(
+                            "{} '{}' reubicado en {}",
+                            output::ok_mark(),
+                            name,
+                            new_path.display()
+                        );
+                        repaired_count += 1;
+                        break;
+                    }
+                    Err(e) => eprintln!("Ruta inválida: {}", e),
+                }
+            },
+            1 => {
+                if index_manager::remove_from_index(index, &[*uuid], true) > 0 {
+                    status!("{} '{}' desregistrado.", output::ok_mark(), name);
+                    repaired_count += 1;
+                }
+            }
+            _ => println!("Omitido."),
+        }
+    }
+
+    Ok(repaired_count)
+}
+
+/// Lanza la interfaz interactiva: elegir un proyecto del árbol y luego una acción.
+fn handle_tui() -> Result<()> {
+    prompt::require_interactive("interfaz interactiva; indica un contexto y una acción")?;
+    let index = index_manager::load_and_ensure_global_project()?;
+    let theme = output::theme();
+
+    // 1. Elegir proyecto. En una terminal interactiva se muestra el árbol; si no, una lista
+    //    plana de nombres cualificados.
+    let project_items: Vec<(Uuid, String)> = if std::io::stdout().is_terminal() {
+        graph_display::build_tree_items(&index)
+    } else {
+        let mut flat: Vec<(Uuid, String)> = index
+            .projects
+            .keys()
+            .filter_map(|uuid| {
+                index_manager::build_qualified_name(*uuid, &index)
+                    .ok()
+                    .map(|name| (*uuid, name))
+            })
+            .collect();
+        flat.sort_by(|(_, a), (_, b)| a.cmp(b));
+        flat
+    };
+    let project_labels: Vec<&str> = project_items.iter().map(|(_, l)| l.as_str()).collect();
+
+    let Some(project_selection) = Select::with_theme(&*theme)
+        .with_prompt("Selecciona un proyecto")
+        .items(&project_labels)
+        .default(0)
+        .interact_opt()?
+    else {
+        println!("Operación cancelada.");
+        return Ok(());
+    };
+    let uuid = project_items[project_selection].0;
+
+    let qualified_name = index_manager::build_qualified_name(uuid, &index)
+        .context("No se pudo reconstruir el nombre del proyecto seleccionado.")?;
+    let config = config_resolver::resolve_config_for_uuid(uuid, qualified_name, &index)?;
+
+    // 2. Elegir acción: las acciones básicas seguidas de los scripts del proyecto.
+    let script_names: Vec<&String> = config.commands.keys().collect();
+    let mut action_labels: Vec<String> = ["start", "info", "tree"]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+    action_labels.extend(script_names.iter().map(|name| format!("run {}", name)));
+
+    let Some(action_selection) = Select::with_theme(&*theme)
+        .with_prompt(format!("Acción para '{}'", config.qualified_name))
+        .items(&action_labels)
+        .default(0)
+        .interact_opt()?
+    else {
+        println!("Operación cancelada.");
+        return Ok(());
+    };
+
+    let (action, args) = match action_labels[action_selection].split_once(' ') {
+        Some(("run", script)) => ("run".to_string(), vec![script.to_string()]),
+        _ => (action_labels[action_selection].clone(), Vec::new()),
+    };
+
+    execute_project_action(config, action, args, SYSTEM_PROJECT_ACTIONS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveal_command_matches_the_platform_file_manager() {
+        let path = Path::new("/home/dev/app");
+        assert_eq!(
+            reveal_command_for(path, "windows"),
+            "explorer \"/home/dev/app\""
+        );
+        assert_eq!(reveal_command_for(path, "macos"), "open -R '/home/dev/app'");
+        assert_eq!(
+            reveal_command_for(path, "linux"),
+            "xdg-open '/home/dev/app'"
+        );
+        assert_eq!(
+            reveal_command_for(path, "freebsd"),
+            "xdg-open '/home/dev/app'"
+        );
+    }
+
+    #[test]
+    fn foreach_parallel_skips_projects_nested_in_running_ones() {
+        let pending = [
+            "global/app/api",
+            "global/app/api/v1",
+            "global/app-web",
+            "global/docs",
+        ];
+        // `app` en marcha bloquea a sus descendientes, pero no a `app-web`.
+        assert_eq!(next_independent(&pending, &["global/app"]), Some(2));
+        // Un descendiente en marcha también bloquea a sus ancestros.
+        assert_eq!(
+            next_independent(&pending[..2], &["global/app/api/v1/x"]),
+            None
+        );
+        assert_eq!(next_independent(&pending, &[]), Some(0));
+        assert_eq!(
+            next_independent(&pending, &["global/app-web", "global/app/api"]),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn run_switches_after_the_separator_belong_to_the_script() {
+        let mut params: Vec<String> = ["--yes", "x", "--", "--yes", "--json"]
+            .map(String::from)
+            .to_vec();
+        let [yes, json] = take_run_switches(&mut params, ["--yes", "--json"]);
+        assert!(yes);
+        assert!(!json);
+        assert_eq!(params, ["x", "--", "--yes", "--json"]);
+    }
+
+    #[test]
+    fn init_rejects_invalid_names_before_touching_the_index() {
+        // La validación ocurre antes de cargar el índice o escribir en disco.
+        for name in ["a/b", "a\\b", "global", "..", "   "] {
+            let error = handle_init(Some(name.to_string()), Vec::new()).unwrap_err();
+            assert!(
+                matches!(
+                    error.downcast_ref::<IndexError>(),
+                    Some(
+                        IndexError::NameHasPathSeparator { .. }
+                            | IndexError::ReservedName { .. }
+                            | IndexError::EmptyName
+                    )
+                ),
+                "'{}' debería rechazarse: {}",
+                name,
+                error
+            );
+        }
+    }
+}
@@ -1,6 +1,7 @@
 // src/cli.rs
 
-use clap::Parser;
+use clap::{ArgAction, Parser};
+use std::path::PathBuf;
 
 /// axes: Un orquestador de flujos de trabajo de desarrollo holístico y jerárquico.
 ///
@@ -15,7 +16,8 @@ use clap::Parser;
 ///    - `axes <acción> <contexto> [args...]`  (ej: `axes info mi-app/api`)
 ///
 ///    Atajos:
-///    - `axes <contexto>` -> se expande a `axes <contexto> start`
+///    - `axes <contexto>` -> se expande a `axes <contexto> start` (o a `run <script>` si
+///      el proyecto define `[options] default_action = "run:<script>"`)
 ///    - `axes <contexto> <script>` -> se expande a `axes <contexto> run <script>`
 ///
 /// 2. MODO SESIÓN (cuando `AXES_PROJECT_UUID` está definido):
@@ -25,8 +27,47 @@ use clap::Parser;
 ///    - `axes <acción> [args...]` (ej: `axes tree`)
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
-#[command(disable_help_subcommand = true)]
+#[command(disable_help_subcommand = true, disable_version_flag = true)]
 pub struct Cli {
+    /// Muestra la versión de axes y dónde guarda su configuración (igual que `axes version`).
+    #[arg(short = 'V', long)]
+    pub version: bool,
+
+    /// Antes de ejecutar la acción, comprueba que las rutas de todos los proyectos
+    /// registrados siguen existiendo y ofrece reubicar o desregistrar las que falten.
+    #[arg(long)]
+    pub validate: bool,
+
+    /// Escribe también los logs (como mínimo de nivel debug) en este archivo.
+    #[arg(long, value_name = "RUTA")]
+    pub log_file: Option<PathBuf>,
+
+    /// Aumenta el nivel de log sin necesidad de `RUST_LOG` (-v info, -vv debug, -vvv trace).
+    #[arg(short, long, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Resuelve los contextos `.` y `_` como si axes se ejecutara desde este directorio.
+    #[arg(long, value_name = "RUTA")]
+    pub cwd: Option<PathBuf>,
+
+    /// Omite la salida decorativa de axes (ecos de comandos, banners, notas).
+    /// La salida de los comandos ejecutados no se ve afectada.
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// No hace preguntas: cualquier selección o dato que haga falta produce un error en lugar
+    /// de esperar respuesta. Se activa solo cuando la entrada estándar no es una terminal.
+    #[arg(long)]
+    pub non_interactive: bool,
+
+    /// Responde "sí" a todas las confirmaciones sin preguntar.
+    #[arg(long, conflicts_with = "no")]
+    pub yes: bool,
+
+    /// Responde "no" a todas las confirmaciones sin preguntar.
+    #[arg(long)]
+    pub no: bool,
+
     /// El primer argumento posicional.
     ///
     /// Su rol depende del modo y de los otros argumentos:
@@ -43,7 +84,8 @@ pub struct Cli {
     /// - Si el primer argumento fue un CONTEXTO, este puede ser una ACCIÓN o el
     ///   nombre de un SCRIPT.
     /// - Para acciones globales (`init`, `register`, `alias`), este es el primer
-    ///   argumento para esa acción (ej. el nombre de un alias).
+    ///   argumento para esa acción (ej. el nombre de un alias o un flag como `--fix`).
+    #[arg(allow_hyphen_values = true)]
     pub action_or_context_or_arg: Option<String>,
 
     /// Todos los argumentos restantes.
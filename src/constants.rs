@@ -9,11 +9,37 @@ pub const PROJECT_CONFIG_FILENAME: &str = "axes.toml";
 /// El nombre del archivo de caché para la configuración resuelta de un proyecto (dentro de .axes/).
 pub const CONFIG_CACHE_FILENAME: &str = "config.cache.bin";
 
+/// Versión del esquema de `config.cache.bin`. Incrementar cada vez que cambie el layout
+/// de `SerializableConfigCache` o de cualquiera de sus tipos internos.
+pub const CONFIG_CACHE_SCHEMA_VERSION: u32 = 16;
+
 /// El nombre del archivo de caché para los hijos de un proyecto (dentro de .axes/).
 pub const CHILDREN_CACHE_FILENAME: &str = "children.cache.bin";
 
 /// El nombre del archivo del índice global (en ~/.config/axes/).
 pub const GLOBAL_INDEX_FILENAME: &str = "index.bin";
+/// Cabecera de `index.bin`. Los índices anteriores no la tienen (ver `LegacyGlobalIndex`).
+pub const GLOBAL_INDEX_MAGIC: &[u8; 4] = b"AXIX";
+/// Versión del formato de `index.bin`, escrita tras la cabecera.
+pub const GLOBAL_INDEX_SCHEMA_VERSION: u32 = 2;
+
+/// El último proyecto usado (`**`), junto a `index.bin` pero en su propio archivo: así
+/// actualizarlo en cada comando no reescribe (ni pisa) el índice completo.
+pub const GLOBAL_LAST_USED_FILENAME: &str = "last_used.bin";
+
+/// El directorio (en ~/.config/axes/) con las plantillas de proyecto del usuario.
+pub const USER_TEMPLATES_DIRNAME: &str = "templates";
+
+/// El directorio (en ~/.config/axes/) con los clones de los repositorios de plantillas
+/// (`init --template-repo`), uno por URL y referencia.
+pub const TEMPLATE_REPOS_DIRNAME: &str = "template-repos";
+
+/// El índice de las primeras versiones de axes: un `index.toml` con proyectos `nombre -> ruta`.
+/// Solo se lee una vez, para migrarlo a `index.bin`.
+pub const LEGACY_INDEX_FILENAME: &str = "index.toml";
+
+/// El nombre del archivo de bloqueo del índice global (junto a `index.bin`).
+pub const GLOBAL_INDEX_LOCK_FILENAME: &str = "index.bin.lock";
 
 /// El nombre del archivo que contiene la identidad y referencias de un proyecto.
 pub const PROJECT_REF_FILENAME: &str = "project_ref.bin";
@@ -0,0 +1,168 @@
+// src/core/config_editor.rs
+
+//! Edición programática de `axes.toml`. Los cambios pasan por `toml_edit`, que conserva
+//! los comentarios, el orden y el formato del usuario: solo se reescribe el valor tocado.
+
+use crate::constants::{AXES_DIR, PROJECT_CONFIG_FILENAME};
+use crate::core::config_resolver;
+use crate::core::context_resolver::{format_suggestion, suggest_similar_name};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use toml_edit::{DocumentMut, InlineTable, Item, Table, Value};
+
+#[derive(Error, Debug)]
+pub enum ConfigEditError {
+    #[error("No se pudo leer o escribir '{path}': {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("'{path}' no es un TOML válido: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml_edit::TomlError,
+    },
+    #[error("La clave '{key}' de '{path}' no es un valor simple; edítala a mano.")]
+    NotAValue { path: PathBuf, key: String },
+    #[error("'{key}' de '{path}' no es una tabla; edítalo a mano.")]
+    NotATable { path: PathBuf, key: String },
+    #[error(
+        "El script '{name}' no existe en '{path}'.{}",
+        format_suggestion(.suggestion)
+    )]
+    CommandNotFound {
+        name: String,
+        path: PathBuf,
+        suggestion: Option<String>,
+    },
+}
+type EditResult<T> = Result<T, ConfigEditError>;
+
+/// Un `axes.toml` abierto para editarlo.
+pub struct ConfigDocument {
+    path: PathBuf,
+    document: DocumentMut,
+}
+
+impl ConfigDocument {
+    /// Abre el `axes.toml` del proyecto en `project_root`.
+    pub fn open(project_root: &Path) -> EditResult<Self> {
+        let path = project_root.join(AXES_DIR).join(PROJECT_CONFIG_FILENAME);
+        let content = fs::read_to_string(&path).map_err(|source| ConfigEditError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let document = config_resolver::strip_bom(&content)
+            .parse::<DocumentMut>()
+            .map_err(|source| ConfigEditError::Parse {
+                path: path.clone(),
+                source,
+            })?;
+        Ok(Self { path, document })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Fija una clave de texto de la raíz (ej. `version`). Si ya existía, se conserva su
+    /// comentario en línea. Devuelve el valor anterior, si era texto.
+    pub fn set_root_string(&mut self, key: &str, value: &str) -> EditResult<Option<String>> {
+        match self.document.get_mut(key) {
+            Some(Item::Value(existing)) => {
+                let previous = existing.as_str().map(str::to_string);
+                let decor = existing.decor().clone();
+                *existing = Value::from(value);
+                *existing.decor_mut() = decor;
+                Ok(previous)
+            }
+            Some(Item::None) | None => {
+                self.document.insert(key, toml_edit::value(value));
+                Ok(None)
+            }
+            Some(_) => Err(ConfigEditError::NotAValue {
+                path: self.path.clone(),
+                key: key.to_string(),
+            }),
+        }
+    }
+
+    /// Indica si `[commands]` ya define el script `name`.
+    pub fn has_command(&self, name: &str) -> bool {
+        self.document
+            .get("commands")
+            .and_then(Item::as_table_like)
+            .is_some_and(|commands| commands.contains_key(name))
+    }
+
+    /// Define (o reemplaza) el script `name` en `[commands]`: como texto simple
+    /// (`name = "run"`) o, con descripción, como tabla en línea (`{ run = ..., desc = ... }`)
+    /// para que quede junto al resto de scripts. Crea `[commands]` si no existe.
+    pub fn set_command(&mut self, name: &str, run: &str, desc: Option<&str>) -> EditResult<()> {
+        let value = match desc {
+            None => Value::from(run),
+            Some(desc) => {
+                let mut table = InlineTable::new();
+                table.insert("run", Value::from(run));
+                table.insert("desc", Value::from(desc));
+                Value::InlineTable(table)
+            }
+        };
+
+        let commands = self
+            .document
+            .entry("commands")
+            .or_insert_with(|| Item::Table(Table::new()));
+        let commands = commands
+            .as_table_like_mut()
+            .ok_or_else(|| ConfigEditError::NotATable {
+                path: self.path.clone(),
+                key: "commands".to_string(),
+            })?;
+        commands.insert(name, Item::Value(value));
+        Ok(())
+    }
+
+    /// Quita el script `name` de `[commands]` sin tocar el resto del archivo. Devuelve el
+    /// fragmento TOML eliminado, para mostrarlo.
+    pub fn remove_command(&mut self, name: &str) -> EditResult<String> {
+        let commands = self
+            .document
+            .get_mut("commands")
+            .and_then(Item::as_table_like_mut);
+        let removed = match commands {
+            Some(commands) => commands.remove(name),
+            None => None,
+        };
+        let Some(removed) = removed else {
+            let names: Vec<String> = self
+                .document
+                .get("commands")
+                .and_then(Item::as_table_like)
+                .map(|commands| commands.iter().map(|(key, _)| key.to_string()).collect())
+                .unwrap_or_default();
+            return Err(ConfigEditError::CommandNotFound {
+                name: name.to_string(),
+                path: self.path.clone(),
+                suggestion: suggest_similar_name(name, names.iter().map(String::as_str)),
+            });
+        };
+
+        // Se renderiza como un documento aparte para que conserve su cabecera `[commands.x]`.
+        let mut commands = Table::new();
+        commands.set_implicit(true);
+        commands.insert(name, removed);
+        let mut snippet = DocumentMut::new();
+        snippet.insert("commands", Item::Table(commands));
+        Ok(snippet.to_string().trim().to_string())
+    }
+
+    /// Escribe el documento en disco.
+    pub fn save(&self) -> EditResult<()> {
+        fs::write(&self.path, self.document.to_string()).map_err(|source| ConfigEditError::Io {
+            path: self.path.clone(),
+            source,
+        })
+    }
+}
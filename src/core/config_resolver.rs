@@ -1,9 +1,12 @@
 // src/core/config_resolver.rs
 
-use crate::constants::{AXES_DIR, CONFIG_CACHE_FILENAME, PROJECT_CONFIG_FILENAME};
+use crate::constants::{
+    AXES_DIR, CONFIG_CACHE_FILENAME, CONFIG_CACHE_SCHEMA_VERSION, PROJECT_CONFIG_FILENAME,
+};
 use crate::models::{
     GlobalIndex, IndexEntry, OptionsConfig, ProjectConfig, ResolvedConfig, SerializableConfigCache,
 };
+use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -37,6 +40,32 @@ pub enum ResolverError {
 
 type ResolverResult<T> = Result<T, ResolverError>;
 
+/// Claves de primer nivel que entiende `axes.toml`. `name` no se guarda en `ProjectConfig`,
+/// pero es válida (solo sirve como variable para los scripts).
+/// Deben mantenerse al día con los campos de `ProjectConfig`.
+const KNOWN_CONFIG_KEYS: &[&str] = &[
+    "name",
+    "version",
+    "description",
+    "commands",
+    "options",
+    "vars",
+    "env",
+    "inherit",
+];
+
+/// Claves válidas dentro de `[options]`. Deben mantenerse al día con `OptionsConfig`.
+const KNOWN_OPTIONS_KEYS: &[&str] = &[
+    "at_start",
+    "at_exit",
+    "shell",
+    "case_insensitive_names",
+    "default_action",
+    "logs",
+    "self_update",
+    "open_with",
+];
+
 // --- FUNCIÓN PÚBLICA PRINCIPAL ---
 
 pub fn resolve_config_for_uuid(
@@ -69,16 +98,18 @@ pub fn resolve_config_for_uuid(
 
     let dependencies = inheritance_chain
         .iter()
-        .map(|(entry, _)| {
+        .map(|(_, entry, _)| {
             let config_path = entry.path.join(AXES_DIR).join(PROJECT_CONFIG_FILENAME);
             let metadata = fs::metadata(&config_path)?;
             Ok((config_path, metadata.modified()?))
         })
         .collect::<ResolverResult<HashMap<_, _>>>()?;
 
-    let configs_in_chain: Vec<ProjectConfig> =
-        inheritance_chain.into_iter().map(|(_, p)| p).collect();
-    let mut resolved_config = merge_chain_into_config(configs_in_chain);
+    let configs_in_chain: Vec<(Uuid, ProjectConfig)> = inheritance_chain
+        .into_iter()
+        .map(|(uuid, _, p)| (uuid, p))
+        .collect();
+    let (mut resolved_config, _) = merge_chain_into_config(configs_in_chain);
 
     resolved_config.uuid = target_uuid;
     resolved_config.qualified_name = qualified_name;
@@ -95,10 +126,36 @@ pub fn resolve_config_for_uuid(
 
 // --- LÓGICA DE HERENCIA (ASCENDENTE) ---
 
+/// Un eslabón de la cadena de herencia, tal y como se lee de su `axes.toml`.
+#[derive(Debug, Clone)]
+pub struct ChainLink {
+    pub uuid: Uuid,
+    pub name: String,
+    pub config_path: PathBuf,
+    pub config: ProjectConfig,
+}
+
+/// Devuelve la cadena de herencia de un proyecto, ordenada de la raíz a la hoja.
+/// No usa ni escribe el caché: siempre lee los `axes.toml` del disco.
+pub fn get_inheritance_chain(
+    leaf_uuid: Uuid,
+    index: &GlobalIndex,
+) -> ResolverResult<Vec<ChainLink>> {
+    Ok(build_inheritance_chain(leaf_uuid, index)?
+        .into_iter()
+        .map(|(uuid, entry, config)| ChainLink {
+            uuid,
+            name: entry.name.clone(),
+            config_path: entry.path.join(AXES_DIR).join(PROJECT_CONFIG_FILENAME),
+            config,
+        })
+        .collect())
+}
+
 fn build_inheritance_chain(
     leaf_uuid: Uuid,
     index: &GlobalIndex,
-) -> ResolverResult<Vec<(&IndexEntry, ProjectConfig)>> {
+) -> ResolverResult<Vec<(Uuid, &IndexEntry, ProjectConfig)>> {
     let mut chain = Vec::new();
     let mut current_uuid_opt = Some(leaf_uuid);
 
@@ -109,7 +166,7 @@ fn build_inheritance_chain(
             .ok_or(ResolverError::UuidNotFoundInIndex { uuid: current_uuid })?;
 
         let config = load_project_config(entry)?;
-        chain.push((entry, config));
+        chain.push((current_uuid, entry, config));
 
         current_uuid_opt = entry.parent;
     }
@@ -120,32 +177,90 @@ fn build_inheritance_chain(
 
 // --- LÓGICA DE FUSIÓN ---
 
-fn merge_chain_into_config(chain: Vec<ProjectConfig>) -> ResolvedConfig {
+/// Para cada clave de `vars`, `env` y `commands`, el UUID del proyecto de la cadena que
+/// aportó el valor final. Es un mapa auxiliar de depuración: nunca se guarda en el caché.
+#[derive(Debug, Clone, Default)]
+pub struct ValueSources {
+    pub vars: HashMap<String, Uuid>,
+    pub env: HashMap<String, Uuid>,
+    pub commands: HashMap<String, Uuid>,
+}
+
+/// Vuelve a fusionar una cadena de herencia solo para saber de dónde sale cada valor.
+pub fn trace_value_sources(chain: &[ChainLink]) -> ValueSources {
+    let configs = chain
+        .iter()
+        .map(|link| (link.uuid, link.config.clone()))
+        .collect();
+    merge_chain_into_config(configs).1
+}
+
+fn merge_chain_into_config(chain: Vec<(Uuid, ProjectConfig)>) -> (ResolvedConfig, ValueSources) {
     let mut resolved = ResolvedConfig {
         uuid: Uuid::nil(),
         qualified_name: String::new(),
         project_root: PathBuf::new(),
         version: None,
         description: None,
-        commands: HashMap::new(),
+        commands: IndexMap::new(),
         options: OptionsConfig::default(),
         vars: HashMap::new(),
         env: HashMap::new(),
     };
+    let mut sources = ValueSources::default();
 
-    for config in chain {
+    for (uuid, config) in chain {
         resolved.version = config.version.or(resolved.version);
         resolved.description = config.description.or(resolved.description);
         resolved.options.at_start = config.options.at_start.or(resolved.options.at_start);
         resolved.options.at_exit = config.options.at_exit.or(resolved.options.at_exit);
         resolved.options.shell = config.options.shell.or(resolved.options.shell);
+        resolved.options.default_action = config
+            .options
+            .default_action
+            .or(resolved.options.default_action);
+        resolved.options.logs = config.options.logs.or(resolved.options.logs);
+        resolved.options.self_update = config.options.self_update.or(resolved.options.self_update);
+        resolved.options.case_insensitive_names = config
+            .options
+            .case_insensitive_names
+            .or(resolved.options.case_insensitive_names);
+
+        // `[inherit]`: un nivel puede descartar lo acumulado antes de aportar lo suyo.
+        if !config.inherit.open_with {
+            resolved.options.open_with.clear();
+        }
+        if !config.inherit.vars {
+            resolved.vars.clear();
+            sources.vars.clear();
+        }
+        if !config.inherit.env {
+            resolved.env.clear();
+            sources.env.clear();
+        }
+        if !config.inherit.commands {
+            resolved.commands.clear();
+            sources.commands.clear();
+        }
+
         resolved.options.open_with.extend(config.options.open_with);
+        sources
+            .vars
+            .extend(config.vars.keys().map(|k| (k.clone(), uuid)));
         resolved.vars.extend(config.vars);
+        sources
+            .env
+            .extend(config.env.keys().map(|k| (k.clone(), uuid)));
         resolved.env.extend(config.env);
-        resolved.commands = config.commands;
+        sources
+            .commands
+            .extend(config.commands.keys().map(|k| (k.clone(), uuid)));
+        // Los comandos se heredan como el resto: `IndexMap::extend` reemplaza en su sitio
+        // los que el nivel redefine.
+        resolved.commands.extend(config.commands);
     }
 
-    resolved
+    (resolved, sources)
 }
 
 // --- LÓGICA DE CARGA Y CACHÉ ---
@@ -159,67 +274,187 @@ fn load_project_config(entry: &IndexEntry) -> ResolverResult<ProjectConfig> {
         });
     }
     let content = fs::read_to_string(&config_path)?;
-    toml::from_str(&content).map_err(|e| ResolverError::TomlParse {
+    let content = strip_bom(&content);
+    let config = toml::from_str(content).map_err(|e| ResolverError::TomlParse {
         path: config_path.display().to_string(),
         source: e,
-    })
+    })?;
+
+    // Serde ignora las claves desconocidas en silencio; una errata como `[comands]` haría
+    // desaparecer los scripts sin explicación, así que avisamos (sin fallar). `axes doctor`
+    // las lista siempre, con `unknown_config_keys`.
+    let unknown = find_unknown_config_keys(content);
+    if !unknown.is_empty() {
+        log::warn!(
+            "'{}' contiene claves desconocidas que se ignorarán: {}",
+            config_path.display(),
+            unknown.join(", ")
+        );
+    }
+
+    Ok(config)
 }
 
-fn read_and_validate_config_cache(
+/// Quita el BOM UTF-8 que algunos editores de Windows añaden al guardar, para que no se
+/// confunda con el inicio de la primera clave. Los finales de línea CRLF ya son TOML válido.
+pub(crate) fn strip_bom(content: &str) -> &str {
+    content.strip_prefix('\u{feff}').unwrap_or(content)
+}
+
+/// Las claves no reconocidas del `axes.toml` de un proyecto, que se ignoran al cargarlo.
+pub fn unknown_config_keys(entry: &IndexEntry) -> ResolverResult<Vec<String>> {
+    let config_path = entry.path.join(AXES_DIR).join(PROJECT_CONFIG_FILENAME);
+    let content = fs::read_to_string(config_path)?;
+    Ok(find_unknown_config_keys(strip_bom(&content)))
+}
+
+/// Devuelve las claves no reconocidas de un `axes.toml` (de primer nivel y de `[options]`),
+/// en el formato en que las escribiría el usuario (ej. `comands`, `options.at_strat`).
+fn find_unknown_config_keys(content: &str) -> Vec<String> {
+    let Ok(table) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+
+    let mut unknown: Vec<String> = table
+        .keys()
+        .filter(|key| !KNOWN_CONFIG_KEYS.contains(&key.as_str()))
+        .cloned()
+        .collect();
+    if let Some(toml::Value::Table(options)) = table.get("options") {
+        unknown.extend(
+            options
+                .keys()
+                .filter(|key| !KNOWN_OPTIONS_KEYS.contains(&key.as_str()))
+                .map(|key| format!("options.{}", key)),
+        );
+    }
+    unknown.sort();
+    unknown
+}
+
+/// El estado del caché de configuración de un proyecto, tal como lo vería la próxima
+/// resolución.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigCacheStatus {
+    /// Válido: se reutilizará.
+    Fresh,
+    /// No existe todavía.
+    Missing,
+    /// Escrito por otra versión de axes.
+    SchemaMismatch { found: u32 },
+    /// No se pudo decodificar.
+    Corrupt(String),
+    /// Pertenece a otro nombre cualificado (el proyecto o un ancestro cambió de nombre).
+    NameMismatch,
+    /// Un `axes.toml` de la cadena de herencia ya no existe.
+    DependencyMissing(PathBuf),
+    /// Un `axes.toml` de la cadena de herencia cambió después de escribir el caché.
+    DependencyModified(PathBuf),
+}
+
+/// Comprueba el caché de configuración de `project_root` sin modificarlo.
+pub fn inspect_config_cache(
+    project_root: &Path,
+    expected_name: &str,
+) -> ResolverResult<ConfigCacheStatus> {
+    let cache_path = project_root.join(AXES_DIR).join(CONFIG_CACHE_FILENAME);
+    check_config_cache(&cache_path, expected_name).map(|(status, _)| status)
+}
+
+/// Decodifica y valida el caché. Devuelve su estado y, si es válido, su contenido.
+fn check_config_cache(
     cache_path: &Path,
     expected_name: &str,
-) -> ResolverResult<Option<ResolvedConfig>> {
+) -> ResolverResult<(ConfigCacheStatus, Option<SerializableConfigCache>)> {
     if !cache_path.exists() {
-        return Ok(None);
+        return Ok((ConfigCacheStatus::Missing, None));
     }
     let cached_bytes = fs::read(cache_path)?;
 
+    // Leer primero solo la versión del esquema. Un caché escrito por otra versión de axes
+    // podría decodificarse "con éxito" en datos basura, así que lo descartamos antes.
+    let version_result: Result<(u32, usize), _> =
+        bincode::serde::decode_from_slice(&cached_bytes, bincode::config::standard());
+    match version_result {
+        Ok((version, _)) if version == CONFIG_CACHE_SCHEMA_VERSION => {}
+        Ok((version, _)) => {
+            return Ok((ConfigCacheStatus::SchemaMismatch { found: version }, None));
+        }
+        Err(e @ DecodeError::Io { .. }) => return Err(ResolverError::BincodeDecode(e)),
+        Err(e) => return Ok((ConfigCacheStatus::Corrupt(e.to_string()), None)),
+    }
+
     let decode_result: Result<(SerializableConfigCache, usize), _> =
         bincode::serde::decode_from_slice(&cached_bytes, bincode::config::standard());
 
     let serializable_cache = match decode_result {
         Ok((cache, _)) => cache, // Asigna directamente el valor que nos interesa
-        Err(e) => {
-            if !matches!(e, DecodeError::Io { .. }) {
-                log::warn!(
-                    "Caché de configuración en '{}' está corrupto o desactualizado. Se regenerará. (Error: {})",
-                    cache_path.display(),
-                    e
-                );
-                let _ = fs::remove_file(cache_path);
-                return Ok(None);
-            }
-            return Err(ResolverError::BincodeDecode(e));
-        }
+        Err(e @ DecodeError::Io { .. }) => return Err(ResolverError::BincodeDecode(e)),
+        Err(e) => return Ok((ConfigCacheStatus::Corrupt(e.to_string()), None)),
     };
 
     if serializable_cache.resolved_config.qualified_name != expected_name {
-        log::debug!("El nombre cualificado del caché no coincide. Caché inválido.");
-        return Ok(None);
+        return Ok((ConfigCacheStatus::NameMismatch, None));
     }
 
     for (path_str, cached_mod_time_serializable) in serializable_cache.dependencies.iter() {
         let path = PathBuf::from(path_str);
         if !path.exists() {
-            log::debug!(
-                "Dependencia de caché '{}' no existe. Caché inválido.",
-                path.display()
-            );
-            return Ok(None);
+            return Ok((ConfigCacheStatus::DependencyMissing(path), None));
         }
         let current_mod_time = fs::metadata(&path)?.modified()?;
         let cached_mod_time: SystemTime = (*cached_mod_time_serializable).into();
 
         if current_mod_time > cached_mod_time {
+            return Ok((ConfigCacheStatus::DependencyModified(path), None));
+        }
+    }
+
+    Ok((ConfigCacheStatus::Fresh, Some(serializable_cache)))
+}
+
+fn read_and_validate_config_cache(
+    cache_path: &Path,
+    expected_name: &str,
+) -> ResolverResult<Option<ResolvedConfig>> {
+    let (status, cache) = check_config_cache(cache_path, expected_name)?;
+    match status {
+        ConfigCacheStatus::Fresh | ConfigCacheStatus::Missing => {}
+        ConfigCacheStatus::SchemaMismatch { found } => {
+            log::warn!(
+                "Caché de configuración en '{}' tiene un esquema obsoleto (v{}, se esperaba v{}). Se regenerará.",
+                cache_path.display(),
+                found,
+                CONFIG_CACHE_SCHEMA_VERSION
+            );
+            let _ = fs::remove_file(cache_path);
+        }
+        ConfigCacheStatus::Corrupt(e) => {
+            log::warn!(
+                "Caché de configuración en '{}' está corrupto o desactualizado. Se regenerará. (Error: {})",
+                cache_path.display(),
+                e
+            );
+            let _ = fs::remove_file(cache_path);
+        }
+        ConfigCacheStatus::NameMismatch => {
+            log::debug!("El nombre cualificado del caché no coincide. Caché inválido.");
+        }
+        ConfigCacheStatus::DependencyMissing(path) => {
+            log::debug!(
+                "Dependencia de caché '{}' no existe. Caché inválido.",
+                path.display()
+            );
+        }
+        ConfigCacheStatus::DependencyModified(path) => {
             log::debug!(
                 "Dependencia de caché '{}' ha sido modificada. Caché inválido.",
                 path.display()
             );
-            return Ok(None);
         }
     }
 
-    Ok(Some(serializable_cache.resolved_config.into()))
+    Ok(cache.map(|cache| cache.resolved_config.into()))
 }
 
 fn write_config_cache(
@@ -238,6 +473,7 @@ fn write_config_cache(
         .collect();
 
     let cache_data = SerializableConfigCache {
+        schema_version: CONFIG_CACHE_SCHEMA_VERSION,
         resolved_config: config.into(),
         dependencies: serializable_deps,
     };
@@ -246,3 +482,185 @@ fn write_config_cache(
     fs::write(cache_path, &bytes)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::index_manager::GLOBAL_PROJECT_UUID;
+    use crate::test_support::TestIndex;
+
+    fn sample_config(root: &Path) -> ResolvedConfig {
+        ResolvedConfig {
+            uuid: Uuid::new_v4(),
+            qualified_name: "global/app".to_string(),
+            project_root: root.to_path_buf(),
+            version: Some("1.0.0".to_string()),
+            description: None,
+            commands: IndexMap::new(),
+            options: OptionsConfig::default(),
+            vars: HashMap::new(),
+            env: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn config_cache_with_old_schema_is_discarded() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join(CONFIG_CACHE_FILENAME);
+        let config = sample_config(dir.path());
+
+        write_config_cache(&cache_path, &config, HashMap::new()).unwrap();
+        let fresh = read_and_validate_config_cache(&cache_path, "global/app").unwrap();
+        assert_eq!(fresh.unwrap().version.as_deref(), Some("1.0.0"));
+
+        let old_cache = SerializableConfigCache {
+            schema_version: CONFIG_CACHE_SCHEMA_VERSION - 1,
+            resolved_config: (&config).into(),
+            dependencies: HashMap::new(),
+        };
+        let bytes = bincode::serde::encode_to_vec(old_cache, bincode::config::standard()).unwrap();
+        fs::write(&cache_path, bytes).unwrap();
+
+        assert_eq!(
+            check_config_cache(&cache_path, "global/app").unwrap().0,
+            ConfigCacheStatus::SchemaMismatch {
+                found: CONFIG_CACHE_SCHEMA_VERSION - 1
+            }
+        );
+        let stale = read_and_validate_config_cache(&cache_path, "global/app").unwrap();
+        assert!(stale.is_none());
+        assert!(!cache_path.exists());
+    }
+
+    fn parse(toml: &str) -> ProjectConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn inherit_false_resets_only_that_collection() {
+        let parent = parse(
+            r#"
+            [commands]
+            build = "cargo build"
+            [vars]
+            out = "dist"
+            [env]
+            RUST_LOG = "info"
+            "#,
+        );
+        let child = parse(
+            r#"
+            [inherit]
+            env = false
+            [env]
+            PORT = "8080"
+            "#,
+        );
+        let grandchild = parse(
+            r#"
+            [vars]
+            mode = "dev"
+            "#,
+        );
+        let (parent_uuid, child_uuid, grandchild_uuid) =
+            (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let (resolved, sources) = merge_chain_into_config(vec![
+            (parent_uuid, parent),
+            (child_uuid, child),
+            (grandchild_uuid, grandchild),
+        ]);
+
+        // `env` empieza de cero en `child` y lo que aporta sigue heredándose hacia abajo.
+        assert_eq!(resolved.env.len(), 1);
+        assert_eq!(resolved.env["PORT"], "8080");
+        assert_eq!(sources.env["PORT"], child_uuid);
+        // El resto de colecciones se siguen heredando del padre.
+        assert_eq!(resolved.vars["out"], "dist");
+        assert_eq!(resolved.vars["mode"], "dev");
+        assert!(resolved.commands.contains_key("build"));
+        assert_eq!(sources.commands["build"], parent_uuid);
+    }
+
+    #[test]
+    fn config_with_bom_and_crlf_is_loaded() {
+        let mut test = TestIndex::new();
+        let app = test.add_with_config(
+            "app",
+            GLOBAL_PROJECT_UUID,
+            "app",
+            "\u{feff}version = \"2.0.0\"\r\n\r\n[vars]\r\nport = \"8080\"\r\n",
+        );
+
+        let config = load_project_config(&test.index.projects[&app]).unwrap();
+        assert_eq!(config.version.as_deref(), Some("2.0.0"));
+        assert_eq!(config.vars["port"], "8080");
+    }
+
+    #[test]
+    fn parse_error_shows_the_offending_line() {
+        let mut test = TestIndex::new();
+        let app = test.add_with_config("app", GLOBAL_PROJECT_UUID, "app", "[vars]\nport = 80 80\n");
+
+        let error = load_project_config(&test.index.projects[&app]).unwrap_err();
+        assert!(matches!(error, ResolverError::TomlParse { .. }));
+        assert!(error.to_string().contains("port = 80 80"));
+    }
+
+    #[test]
+    fn unknown_keys_cover_tables_and_options() {
+        let mut test = TestIndex::new();
+        let app = test.add_with_config(
+            "app",
+            GLOBAL_PROJECT_UUID,
+            "app",
+            "version = \"1.0.0\"\n\n[comands]\nbuild = \"cargo build\"\n\n[options]\nshell = \"bash\"\nat_strat = \"echo hola\"\n",
+        );
+
+        let keys = unknown_config_keys(&test.index.projects[&app]).unwrap();
+        assert_eq!(keys, ["comands", "options.at_strat"]);
+    }
+
+    #[test]
+    fn command_order_survives_merge_and_cache() {
+        let parent = parse(
+            r#"
+            [commands]
+            zeta = "echo z"
+            alpha = "echo a"
+            mid = "echo m"
+            "#,
+        );
+        let child = parse(
+            r#"
+            [commands]
+            extra = "echo e"
+            alpha = "echo A"
+            "#,
+        );
+        let order =
+            |config: &ResolvedConfig| -> Vec<String> { config.commands.keys().cloned().collect() };
+        assert_eq!(
+            parent.commands.keys().collect::<Vec<_>>(),
+            ["zeta", "alpha", "mid"]
+        );
+
+        let (mut resolved, _) =
+            merge_chain_into_config(vec![(Uuid::new_v4(), parent), (Uuid::new_v4(), child)]);
+        // Los del padre primero, en su orden; `alpha` se redefine sin moverse.
+        assert_eq!(order(&resolved), ["zeta", "alpha", "mid", "extra"]);
+        assert!(matches!(
+            &resolved.commands["alpha"],
+            crate::models::Command::Simple(run) if run == "echo A"
+        ));
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join(CONFIG_CACHE_FILENAME);
+        resolved.qualified_name = "global/app".to_string();
+        write_config_cache(&cache_path, &resolved, HashMap::new()).unwrap();
+        let cached = read_and_validate_config_cache(&cache_path, "global/app")
+            .unwrap()
+            .unwrap();
+        assert_eq!(order(&cached), order(&resolved));
+    }
+}
@@ -1,12 +1,15 @@
 // src/core/context_resolver.rs
 
 use crate::models::{GlobalIndex, IndexEntry, LastUsedCache};
-use dialoguer::{Error as DialoguerError, Select, theme::ColorfulTheme};
+use crate::output;
+use crate::prompt;
+use dialoguer::{Error as DialoguerError, Select};
 use std::{env, fs, path::Path};
 use thiserror::Error;
 use uuid::Uuid;
 
 use crate::constants::AXES_DIR;
+use crate::core::config_resolver;
 use crate::core::index_manager::{self, GLOBAL_PROJECT_UUID};
 
 use crate::constants::LAST_USED_CACHE_FILENAME;
@@ -25,6 +28,8 @@ pub enum ContextError {
     BincodeEncode(#[from] bincode::error::EncodeError),
     #[error("Error de Interfaz de Usuario: {0}")]
     Dialoguer(#[from] DialoguerError),
+    #[error("{0}")]
+    Prompt(#[from] prompt::PromptError),
     #[error("Contexto vacío no proporcionado.")]
     EmptyContext,
     #[error("El contexto '**' solo puede ser usado al inicio de la ruta.")]
@@ -43,34 +48,76 @@ pub enum ContextError {
     ProjectNotFoundFromPath,
     #[error("No se encontró ningún proyecto de axes en el directorio actual.")]
     ProjectNotFoundInCwd,
-    #[error("No se encontró el proyecto raíz con el nombre '{name}'.")]
-    RootProjectNotFound { name: String },
-    #[error("El proyecto hijo '{child_name}' no se encontró para el padre '{parent_name}'.")]
+    #[error(
+        "No se encontró el proyecto raíz con el nombre '{name}'.{}",
+        format_suggestion(.suggestion)
+    )]
+    RootProjectNotFound {
+        name: String,
+        suggestion: Option<String>,
+    },
+    #[error(
+        "El proyecto hijo '{child_name}' no se encontró para el padre '{parent_name}'.{}",
+        format_suggestion(.suggestion)
+    )]
     ChildProjectNotFound {
         child_name: String,
         parent_name: String,
+        suggestion: Option<String>,
     },
     #[error("El alias '{name}!' no fue encontrado.")]
     AliasNotFound { name: String },
-    #[error(
-        "No se pudo resolver el nombre del proyecto para el alias (posible enlace de padre roto)."
-    )]
-    AliasResolutionError,
+    #[error("No hay ningún proyecto registrado con el UUID '{uuid}'.")]
+    UuidNotFound { uuid: Uuid },
     #[error("Operación cancelada por el usuario.")]
     Cancelled,
+    #[error(
+        "El índice global es inconsistente: falta la entrada del proyecto '{uuid}'. Ejecuta `axes doctor --fix` para repararlo."
+    )]
+    InconsistentIndex { uuid: Uuid },
 }
 
 type ContextResult<T> = Result<T, ContextError>;
 
+/// Obtiene la entrada de un UUID que el propio índice referencia (padre, alias, hijo...).
+/// Si falta, el índice está corrupto o editado a mano: se devuelve un error en vez de entrar en pánico.
+fn get_entry(index: &GlobalIndex, uuid: Uuid) -> ContextResult<&IndexEntry> {
+    index
+        .projects
+        .get(&uuid)
+        .ok_or(ContextError::InconsistentIndex { uuid })
+}
+
+/// Formatea la sugerencia "¿quisiste decir...?" que acompaña a los errores de nombre.
+pub(crate) fn format_suggestion(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(name) => format!(" ¿Quisiste decir '{}'?", name),
+        None => String::new(),
+    }
+}
+
 /// Resuelve una ruta de proyecto a un UUID y un nombre cualificado.
 pub fn resolve_context(context: &str, index: &GlobalIndex) -> ContextResult<(Uuid, String)> {
+    resolve_context_from(context, index, None)
+}
+
+/// Como `resolve_context`, pero `.` y `_` se resuelven desde `base_dir` en lugar del
+/// directorio actual del proceso (útil para `--cwd`).
+pub fn resolve_context_from(
+    context: &str,
+    index: &GlobalIndex,
+    base_dir: Option<&Path>,
+) -> ContextResult<(Uuid, String)> {
     let parts: Vec<&str> = context.split('/').filter(|s| !s.is_empty()).collect();
     if parts.is_empty() {
         return Err(ContextError::EmptyContext);
     }
 
+    let case_insensitive = case_insensitive_names_enabled(index);
+
     // 1. `resolve_first_part` ahora maneja toda la lógica inicial.
-    let (mut current_uuid, mut current_parent_uuid) = resolve_first_part(parts[0], index)?;
+    let (mut current_uuid, mut current_parent_uuid) =
+        resolve_first_part(parts[0], index, case_insensitive, base_dir)?;
 
     // 2. Si no es un alias, proceder con la resolución de ruta normal.
     //let parts: Vec<&str> = context.split('/').filter(|s| !s.is_empty()).collect();
@@ -85,18 +132,19 @@ pub fn resolve_context(context: &str, index: &GlobalIndex) -> ContextResult<(Uui
             "." | "_" => return Err(ContextError::LocalPathNotAtStart),
             ".." => {
                 let parent_uuid = current_parent_uuid.ok_or(ContextError::AlreadyAtRoot)?;
-                let parent_entry = index.projects.get(&parent_uuid).unwrap(); // Seguro
+                let parent_entry = get_entry(index, parent_uuid)?;
                 (parent_uuid, parent_entry.parent)
             }
             "*" => {
-                let parent_entry = index.projects.get(&current_uuid).unwrap(); // Seguro
+                let parent_entry = get_entry(index, current_uuid)?;
                 let child_uuid = resolve_last_used_child(current_uuid, parent_entry, index)?;
                 //let child_entry = index.projects.get(&child_uuid).unwrap(); // Seguro
                 (child_uuid, Some(current_uuid))
             }
             name => {
-                let parent_entry = index.projects.get(&current_uuid).unwrap(); // Seguro
-                let child_uuid = find_child_by_name(current_uuid, parent_entry, name, index)?;
+                let parent_entry = get_entry(index, current_uuid)?;
+                let child_uuid =
+                    find_child_by_name(current_uuid, parent_entry, name, index, case_insensitive)?;
                 //let child_entry = index.projects.get(&child_uuid).unwrap(); // Seguro
                 (child_uuid, Some(current_uuid))
             }
@@ -109,14 +157,18 @@ pub fn resolve_context(context: &str, index: &GlobalIndex) -> ContextResult<(Uui
     update_last_used_caches(current_uuid, index)?;
 
     // Reconstruir el nombre cualificado completo para el UUID final.
-    let final_qualified_name = index_manager::build_qualified_name(current_uuid, index)
-        .ok_or(ContextError::AliasResolutionError)?; // Reutilizamos el error
+    let final_qualified_name = index_manager::build_qualified_name(current_uuid, index)?;
 
     Ok((current_uuid, final_qualified_name))
 }
 
 /// Resuelve la primera parte de la ruta, que tiene reglas especiales.
-fn resolve_first_part(part: &str, index: &GlobalIndex) -> ContextResult<(Uuid, Option<Uuid>)> {
+fn resolve_first_part(
+    part: &str,
+    index: &GlobalIndex,
+    case_insensitive: bool,
+    base_dir: Option<&Path>,
+) -> ContextResult<(Uuid, Option<Uuid>)> {
     // 1. Comprobar si es un alias.
     if let Some(alias_name) = part.strip_suffix('!') {
         let uuid = index
@@ -126,31 +178,63 @@ fn resolve_first_part(part: &str, index: &GlobalIndex) -> ContextResult<(Uuid, O
                 name: alias_name.to_string(),
             })?;
 
-        let entry = index.projects.get(uuid).unwrap(); // Es seguro si el índice es consistente.
+        let entry = get_entry(index, *uuid)?;
         return Ok((*uuid, entry.parent));
     }
 
-    // 2. Si no es un alias, usar la lógica de palabras clave y nombres de raíz.
+    // 2. Comprobar si es un UUID literal (útil en scripts y para depurar nombres rotos).
+    if let Ok(uuid) = Uuid::parse_str(part) {
+        let entry = index
+            .projects
+            .get(&uuid)
+            .ok_or(ContextError::UuidNotFound { uuid })?;
+        return Ok((uuid, entry.parent));
+    }
+
+    // 3. Si no, usar la lógica de palabras clave y nombres de raíz.
     let uuid = match part {
-        "**" => index.last_used.ok_or(ContextError::NoLastUsedProject)?,
+        // Un `last_used` que apunta a un proyecto ya borrado equivale a no tener ninguno.
+        "**" => index
+            .last_used
+            .filter(|uuid| index.projects.contains_key(uuid))
+            .ok_or(ContextError::NoLastUsedProject)?,
         "*" => {
-            let global_entry = index
-                .projects
-                .get(&GLOBAL_PROJECT_UUID)
-                .expect("El proyecto global debe existir siempre.");
+            let global_entry = get_entry(index, GLOBAL_PROJECT_UUID)?;
             resolve_last_used_child(GLOBAL_PROJECT_UUID, global_entry, index)?
         }
-        "." => find_project_from_path(&env::current_dir()?, true, index)?,
-        "_" => find_project_from_path(&env::current_dir()?, false, index)?,
+        "." | "_" => {
+            let base = match base_dir {
+                Some(dir) => dir.to_path_buf(),
+                None => env::current_dir()?,
+            };
+            find_project_from_path(&base, part == ".", index)?
+        }
         // **"global" es un nombre explícito, el resto son hijos implícitos de `global`.
-        "global" => GLOBAL_PROJECT_UUID,
+        name if names_match(name, "global", case_insensitive) => GLOBAL_PROJECT_UUID,
         name => {
             // Es una ruta implícita, buscar como hijo de `global`.
-            let global_entry = index.projects.get(&GLOBAL_PROJECT_UUID).unwrap(); // Es seguro.
-            find_child_by_name(GLOBAL_PROJECT_UUID, global_entry, name, index)?
+            let global_entry = get_entry(index, GLOBAL_PROJECT_UUID)?;
+            find_child_by_name(
+                GLOBAL_PROJECT_UUID,
+                global_entry,
+                name,
+                index,
+                case_insensitive,
+            )
+            .map_err(|e| match e {
+                ContextError::ChildProjectNotFound {
+                    child_name,
+                    suggestion,
+                    ..
+                } => ContextError::RootProjectNotFound {
+                    name: child_name,
+                    suggestion,
+                },
+                other => other,
+            })?
         }
     };
-    let entry = index.projects.get(&uuid).unwrap();
+    let entry = get_entry(index, uuid)?;
     Ok((uuid, entry.parent))
 }
 
@@ -164,8 +248,13 @@ fn resolve_last_used_child(
         .path
         .join(AXES_DIR)
         .join(LAST_USED_CACHE_FILENAME);
+    // El caché puede apuntar a un hijo ya desregistrado o movido: en ese caso se ignora.
     if let Ok(Some(cache)) = read_last_used_cache(&cache_path)
         && let Some(uuid) = cache.child_uuid
+        && index
+            .projects
+            .get(&uuid)
+            .is_some_and(|child| child.parent == Some(parent_uuid))
     {
         log::debug!(
             "Último hijo usado '{}' encontrado en caché para '{}'.",
@@ -197,7 +286,8 @@ fn resolve_last_used_child(
         "El proyecto '{}' no tiene un hijo usado recientemente.",
         parent_entry.name
     );
-    let selection = Select::with_theme(&ColorfulTheme::default())
+    prompt::require_interactive(&format!("selección de un hijo de '{}'", parent_entry.name))?;
+    let selection = Select::with_theme(&*output::theme())
         .with_prompt("Por favor, selecciona un hijo para continuar:")
         .items(&child_names)
         .default(0)
@@ -205,62 +295,176 @@ fn resolve_last_used_child(
         .ok_or(ContextError::Cancelled)?;
 
     let selected_name = child_names[selection];
-    find_child_by_name(parent_uuid, parent_entry, selected_name, index)
+    find_child_by_name(parent_uuid, parent_entry, selected_name, index, false)
 }
 
+/// Tope de directorios que se suben buscando un proyecto; solo es una red de seguridad, ya
+/// que la ruta canonicalizada no tiene enlaces simbólicos y siempre termina en la raíz.
+const MAX_ANCESTOR_DEPTH: usize = 256;
+
 /// Encuentra el UUID de un proyecto buscando desde una ruta del sistema de archivos.
-fn find_project_from_path(
+/// Con `search_up`, devuelve el proyecto registrado más cercano que contiene la ruta.
+///
+/// La ruta se canonicaliza antes de subir, así que los enlaces simbólicos de los ancestros
+/// se resuelven una sola vez y la búsqueda recorre los directorios reales hasta la raíz.
+pub fn find_project_from_path(
     path: &Path,
     search_up: bool,
     index: &GlobalIndex,
 ) -> ContextResult<Uuid> {
     let current_path = dunce::canonicalize(path)?;
-
-    if search_up {
-        // Modo '.' (búsqueda ascendente)
-        let mut candidates: Vec<(Uuid, &IndexEntry)> = index
+    let project_at = |dir: &Path| {
+        index
             .projects
             .iter()
-            .filter(|(_, entry)| current_path.starts_with(&entry.path))
-            .map(|(uuid, entry)| (*uuid, entry))
-            .collect();
-
-        if candidates.is_empty() {
-            return Err(ContextError::ProjectNotFoundFromPath);
-        }
-
-        // Ordenar por longitud de la ruta, de más larga a más corta.
-        candidates.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.path.as_os_str().len()));
+            .find(|(_, entry)| index_manager::entry_path_eq(entry, dir))
+            .map(|(uuid, _)| *uuid)
+    };
 
-        // El primer candidato es el más específico (el "ancestro" más cercano).
-        Ok(candidates[0].0)
+    if search_up {
+        // Modo '.' (búsqueda ascendente): el primer ancestro registrado es el más cercano.
+        current_path
+            .ancestors()
+            .take(MAX_ANCESTOR_DEPTH)
+            .find_map(project_at)
+            .ok_or(ContextError::ProjectNotFoundFromPath)
     } else {
         // Modo '_' (búsqueda estricta en el directorio actual)
-        index
-            .projects
-            .iter()
-            .find(|(_, entry)| entry.path == current_path)
-            .map(|(uuid, _)| *uuid)
-            .ok_or(ContextError::ProjectNotFoundInCwd)
+        project_at(&current_path).ok_or(ContextError::ProjectNotFoundInCwd)
     }
 }
 
 /// Encuentra el UUID de un hijo por su nombre (lógica movida de config_resolver).
+/// Con `case_insensitive`, si varios hermanos difieren solo en mayúsculas se pregunta al usuario.
 fn find_child_by_name(
     parent_uuid: Uuid,
     parent_entry: &IndexEntry,
     child_name: &str,
     index: &GlobalIndex,
+    case_insensitive: bool,
 ) -> ContextResult<Uuid> {
-    index
+    // Atajo: el caché `nombre -> UUID` del padre. Solo sirve para nombres exactos, y cada
+    // acierto se comprueba contra el índice, así que un caché obsoleto nunca da un resultado
+    // incorrecto: simplemente se regenera.
+    if !case_insensitive {
+        let cached = index_manager::read_child_cache(parent_entry)
+            .and_then(|cache| cache.children.get(child_name).copied())
+            .filter(|uuid| {
+                index.projects.get(uuid).is_some_and(|child| {
+                    child.parent == Some(parent_uuid) && child.name == child_name
+                })
+            });
+        if let Some(uuid) = cached {
+            return Ok(uuid);
+        }
+        if let Some(uuid) = index_manager::write_child_cache(index, parent_uuid)
+            .children
+            .get(child_name)
+        {
+            return Ok(*uuid);
+        }
+    }
+
+    let mut candidates: Vec<(Uuid, &IndexEntry)> = index
         .projects
         .iter()
-        .find(|(_, e)| e.parent == Some(parent_uuid) && e.name == child_name)
-        .map(|(uuid, _)| *uuid)
-        .ok_or_else(|| ContextError::ChildProjectNotFound {
-            child_name: child_name.to_string(),
-            parent_name: parent_entry.name.clone(),
+        .filter(|(_, e)| {
+            e.parent == Some(parent_uuid) && names_match(&e.name, child_name, case_insensitive)
         })
+        .map(|(uuid, e)| (*uuid, e))
+        .collect();
+
+    match candidates.len() {
+        0 => {
+            let sibling_names = index
+                .projects
+                .values()
+                .filter(|e| e.parent == Some(parent_uuid))
+                .map(|e| e.name.as_str());
+            Err(ContextError::ChildProjectNotFound {
+                child_name: child_name.to_string(),
+                parent_name: parent_entry.name.clone(),
+                suggestion: suggest_similar_name(child_name, sibling_names),
+            })
+        }
+        1 => Ok(candidates[0].0),
+        _ => {
+            candidates.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+            let names: Vec<&str> = candidates.iter().map(|(_, e)| e.name.as_str()).collect();
+            println!(
+                "Varios hijos de '{}' coinciden con '{}' sin distinguir mayúsculas.",
+                parent_entry.name, child_name
+            );
+            prompt::require_interactive(&format!(
+                "elegir entre varios hijos que coinciden con '{}'",
+                child_name
+            ))?;
+            let selection = Select::with_theme(&*output::theme())
+                .with_prompt("Por favor, selecciona el proyecto correcto:")
+                .items(&names)
+                .default(0)
+                .interact_opt()?
+                .ok_or(ContextError::Cancelled)?;
+            Ok(candidates[selection].0)
+        }
+    }
+}
+
+/// Compara dos nombres de proyecto, opcionalmente sin distinguir mayúsculas.
+pub(crate) fn names_match(a: &str, b: &str, case_insensitive: bool) -> bool {
+    if case_insensitive {
+        a.to_lowercase() == b.to_lowercase()
+    } else {
+        a == b
+    }
+}
+
+/// Lee `[options] case_insensitive_names` del proyecto `global`, a través de su caché de
+/// configuración para no volver a parsear el `axes.toml` en cada resolución.
+/// Si no se puede resolver, se asume el comportamiento por defecto (sensible a mayúsculas).
+pub(crate) fn case_insensitive_names_enabled(index: &GlobalIndex) -> bool {
+    config_resolver::resolve_config_for_uuid(GLOBAL_PROJECT_UUID, "global".to_string(), index)
+        .ok()
+        .and_then(|config| config.options.case_insensitive_names)
+        .unwrap_or(false)
+}
+
+/// Devuelve el candidato más parecido a `requested` si está lo bastante cerca
+/// (distancia de Levenshtein, sin distinguir mayúsculas) como para ser un error tipográfico.
+pub(crate) fn suggest_similar_name<'a>(
+    requested: &str,
+    candidates: impl Iterator<Item = &'a str>,
+) -> Option<String> {
+    let requested_lower = requested.to_lowercase();
+    let max_distance = (requested.chars().count() / 3).max(2);
+
+    candidates
+        .map(|candidate| {
+            let distance = levenshtein(&requested_lower, &candidate.to_lowercase());
+            (distance, candidate)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by(|(da, a), (db, b)| da.cmp(db).then_with(|| a.cmp(b)))
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Distancia de edición de Levenshtein entre dos cadenas (por caracteres).
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1; b_chars.len() + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution_cost = if a_char == *b_char { 0 } else { 1 };
+            current_row[j + 1] = (previous_row[j] + substitution_cost)
+                .min(previous_row[j + 1] + 1)
+                .min(current_row[j] + 1);
+        }
+        previous_row = current_row;
+    }
+
+    previous_row[b_chars.len()]
 }
 
 /// Lee el caché de "último usado" de un proyecto padre.
@@ -302,14 +506,19 @@ fn write_last_used_cache(path: &Path, cache: &LastUsedCache) -> ContextResult<()
     Ok(())
 }
 
+/// Actualiza el `last_used` global y, subiendo desde `final_uuid`, el caché `*` de cada
+/// ancestro con su hijo inmediato en el camino: tras usar `a/b/c`, `a` recuerda `b` y
+/// `b` recuerda `c`, de modo que `a/*/*` vuelve a resolver `a/b/c`.
 fn update_last_used_caches(final_uuid: Uuid, index: &GlobalIndex) -> ContextResult<()> {
-    // 1. Actualizar el `last_used` global.
-    let mut global_index = index_manager::load_and_ensure_global_project()?;
-    global_index.last_used = Some(final_uuid);
-    index_manager::save_global_index(&global_index)?;
+    // 1. Actualizar el `last_used` global, solo si cambió. Va en su propio archivo, así que
+    //    no se reescribe el índice (ni se pisan cambios concurrentes de otro proceso).
+    if index.last_used != Some(final_uuid) {
+        index_manager::save_global_last_used(final_uuid)?;
+    }
 
     // 2. Actualizar los cachés de hijos (`*`) subiendo por el árbol.
-    let mut current_entry = index.projects.get(&final_uuid).unwrap();
+    let mut current_entry = get_entry(index, final_uuid)?;
+    // El hijo inmediato del padre que se está visitando (no el destino final).
     let mut child_uuid_to_save = final_uuid;
 
     // Subir por la cadena de herencia
@@ -320,18 +529,21 @@ fn update_last_used_caches(final_uuid: Uuid, index: &GlobalIndex) -> ContextResu
                 parent_entry.name,
                 child_uuid_to_save
             );
-            let cache = LastUsedCache {
-                child_uuid: Some(child_uuid_to_save),
-            };
             let cache_path = parent_entry
                 .path
                 .join(AXES_DIR)
                 .join(LAST_USED_CACHE_FILENAME);
 
-            // Llamar a la función que antes no se usaba
-            write_last_used_cache(&cache_path, &cache)?;
+            // Solo se escribe si el hijo recordado cambió.
+            let current = read_last_used_cache(&cache_path).ok().flatten();
+            if current.and_then(|c| c.child_uuid) != Some(child_uuid_to_save) {
+                let cache = LastUsedCache {
+                    child_uuid: Some(child_uuid_to_save),
+                };
+                write_last_used_cache(&cache_path, &cache)?;
+            }
 
-            // Preparar para la siguiente iteración
+            // Preparar para la siguiente iteración: para el abuelo, el hijo es este padre.
             child_uuid_to_save = parent_uuid;
             current_entry = parent_entry;
         } else {
@@ -342,3 +554,165 @@ fn update_last_used_caches(final_uuid: Uuid, index: &GlobalIndex) -> ContextResu
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestIndex;
+
+    #[test]
+    fn suggests_close_sibling_name() {
+        let siblings = ["frontend", "backend", "docs"];
+        assert_eq!(
+            suggest_similar_name("backedn", siblings.into_iter()),
+            Some("backend".to_string())
+        );
+        assert_eq!(
+            suggest_similar_name("Docs", siblings.into_iter()),
+            Some("docs".to_string())
+        );
+
+        let error = ContextError::RootProjectNotFound {
+            name: "backedn".to_string(),
+            suggestion: suggest_similar_name("backedn", siblings.into_iter()),
+        };
+        assert!(error.to_string().ends_with("¿Quisiste decir 'backend'?"));
+    }
+
+    #[test]
+    fn no_suggestion_when_nothing_is_close() {
+        let siblings = ["frontend", "backend", "docs"];
+        assert_eq!(suggest_similar_name("infra", siblings.into_iter()), None);
+        assert_eq!(suggest_similar_name("api", std::iter::empty()), None);
+        assert_eq!(format_suggestion(&None), "");
+    }
+
+    #[test]
+    fn resolves_context_by_uuid() {
+        let mut test = TestIndex::new();
+        let app = test.add("app", GLOBAL_PROJECT_UUID, "app");
+        let api = test.add("api", app, "app/api");
+
+        let (uuid, qualified_name) = resolve_context(&api.to_string(), &test.index).unwrap();
+        assert_eq!(uuid, api);
+        assert_eq!(qualified_name, "global/app/api");
+
+        // Un UUID como primer segmento admite el resto de la sintaxis.
+        let (uuid, _) = resolve_context(&format!("{}/..", api), &test.index).unwrap();
+        assert_eq!(uuid, app);
+
+        let unknown = Uuid::new_v4();
+        assert!(matches!(
+            resolve_context(&unknown.to_string(), &test.index),
+            Err(ContextError::UuidNotFound { uuid }) if uuid == unknown
+        ));
+    }
+
+    #[test]
+    fn last_used_caches_reproduce_the_resolved_path() {
+        let mut test = TestIndex::new();
+        let a = test.add("a", GLOBAL_PROJECT_UUID, "a");
+        let b = test.add("b", a, "a/b");
+        let c = test.add("c", b, "a/b/c");
+        // Hermanos que `*` no debe elegir.
+        test.add("x", a, "a/x");
+        test.add("y", b, "a/b/y");
+
+        let (resolved, _) = resolve_context("a/b/c", &test.index).unwrap();
+        assert_eq!(resolved, c);
+
+        let (uuid, qualified_name) = resolve_context("a/*/*", &test.index).unwrap();
+        assert_eq!(uuid, c);
+        assert_eq!(qualified_name, "global/a/b/c");
+        // Y `*` en la raíz recuerda `a`.
+        assert_eq!(resolve_context("*/b", &test.index).unwrap().0, b);
+    }
+
+    #[test]
+    fn resolution_follows_add_rename_and_link() {
+        let mut test = TestIndex::new();
+        let app = test.add("app", GLOBAL_PROJECT_UUID, "app");
+        let api = test.add("api", app, "app/api");
+
+        // La primera resolución escribe el caché de hijos de `app`.
+        assert_eq!(resolve_context("app/api", &test.index).unwrap().0, api);
+        let app_entry = &test.index.projects[&app];
+        assert!(index_manager::read_child_cache(app_entry).is_some());
+        // Las vistas previas (`--dry-run`) no tocan el caché en disco.
+        index_manager::preview_rename(&test.index, api, "backend").unwrap();
+        index_manager::preview_link(&test.index, api, GLOBAL_PROJECT_UUID).unwrap();
+        assert!(index_manager::read_child_cache(app_entry).is_some());
+
+        let (web, _) = index_manager::add_project_to_index(
+            &mut test.index,
+            "web".to_string(),
+            test.root.join("app/web"),
+            Some(app),
+        )
+        .unwrap();
+        assert_eq!(resolve_context("app/web", &test.index).unwrap().0, web);
+
+        index_manager::rename_project(&mut test.index, api, "backend").unwrap();
+        assert_eq!(resolve_context("app/backend", &test.index).unwrap().0, api);
+        assert!(resolve_context("app/api", &test.index).is_err());
+
+        index_manager::link_project(&mut test.index, web, GLOBAL_PROJECT_UUID).unwrap();
+        assert_eq!(resolve_context("web", &test.index).unwrap().0, web);
+        assert!(resolve_context("app/web", &test.index).is_err());
+    }
+
+    #[test]
+    fn parent_dot_creates_a_child_of_the_current_project() {
+        let mut test = TestIndex::new();
+        let app = test.add("app", GLOBAL_PROJECT_UUID, "app");
+        let inside = test.root.join("app/src/bin");
+        fs::create_dir_all(&inside).unwrap();
+
+        // Así resuelve `init`/`link` el valor de `--parent`.
+        let (parent, _) = resolve_context_from(".", &test.index, Some(&inside)).unwrap();
+        assert_eq!(parent, app);
+        let app_root = test.root.join("app");
+        assert_eq!(
+            resolve_context_from("_", &test.index, Some(&app_root))
+                .unwrap()
+                .0,
+            app
+        );
+
+        let (child, _) = index_manager::add_project_to_index(
+            &mut test.index,
+            "tool".to_string(),
+            inside.clone(),
+            Some(parent),
+        )
+        .unwrap();
+        let (uuid, qualified_name) = resolve_context("app/tool", &test.index).unwrap();
+        assert_eq!(uuid, child);
+        assert_eq!(qualified_name, "global/app/tool");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_project_resolves_to_its_real_location() {
+        let mut test = TestIndex::new();
+        let app = test.add("app", GLOBAL_PROJECT_UUID, "real/app");
+        let outer = test.add("outer", GLOBAL_PROJECT_UUID, "outer");
+        fs::create_dir_all(test.root.join("real/app/src")).unwrap();
+        // Un enlace dentro de `outer` que apunta a `app`.
+        let link = test.root.join("outer/link");
+        std::os::unix::fs::symlink(test.root.join("real/app"), &link).unwrap();
+
+        assert_eq!(
+            find_project_from_path(&link.join("src"), true, &test.index).unwrap(),
+            app
+        );
+        assert_eq!(
+            find_project_from_path(&link, false, &test.index).unwrap(),
+            app
+        );
+        assert_eq!(
+            find_project_from_path(&test.root.join("outer"), true, &test.index).unwrap(),
+            outer
+        );
+    }
+}
@@ -1,121 +1,411 @@
 // src/core/graph_display.rs
 
+use crate::core::index_manager;
 use crate::models::{GlobalIndex, IndexEntry};
-use std::collections::HashMap;
+use crate::output;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
-/// Muestra un árbol ASCII de todos los proyectos registrados.
-pub fn display_project_tree(index: &GlobalIndex, start_node_uuid: Option<Uuid>) {
+/// Formato de salida de `axes tree`. Todos comparten el mismo recorrido del árbol; solo
+/// cambia cómo se dibuja cada nodo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TreeFormat {
+    /// Árbol con conectores (`├─`, `└─`; `|-`, `` `- `` sin colores), para la terminal.
+    #[default]
+    Ascii,
+    /// Lista anidada de Markdown, para documentación.
+    Markdown,
+    /// Grafo de Graphviz (`dot -Tpng`), con aristas padre → hijo.
+    Dot,
+}
+
+impl TreeFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ascii" => Some(TreeFormat::Ascii),
+            "md" | "markdown" => Some(TreeFormat::Markdown),
+            "dot" => Some(TreeFormat::Dot),
+            _ => None,
+        }
+    }
+
+    fn header(self, whole_tree: bool) -> Vec<String> {
+        match self {
+            TreeFormat::Ascii if whole_tree => {
+                vec!["\nÁrbol de Proyectos Registrados:".to_string()]
+            }
+            TreeFormat::Ascii | TreeFormat::Markdown => Vec::new(),
+            TreeFormat::Dot => vec![
+                "digraph axes {".to_string(),
+                "  rankdir=LR;".to_string(),
+                "  node [shape=box];".to_string(),
+            ],
+        }
+    }
+
+    fn footer(self) -> Vec<String> {
+        match self {
+            TreeFormat::Dot => vec!["}".to_string()],
+            TreeFormat::Ascii | TreeFormat::Markdown => Vec::new(),
+        }
+    }
+
+    /// Las líneas que representan un nodo (y, en DOT, la arista desde su padre).
+    fn render_node(self, node: &TreeNode) -> Vec<String> {
+        match self {
+            TreeFormat::Ascii => {
+                // El nodo de inicio de un subárbol se dibuja como raíz, sin conector.
+                let connector = if node.is_start {
+                    ""
+                } else {
+                    branch(node.is_last)
+                };
+                let last_used_marker = if node.is_last_used { " (**)" } else { "" };
+                vec![format!(
+                    "{}{}{} [{}] {}",
+                    node.prefix,
+                    connector,
+                    node.entry.name,
+                    node.entry.path.display(),
+                    last_used_marker
+                )]
+            }
+            TreeFormat::Markdown => {
+                let last_used_marker = if node.is_last_used {
+                    " _(último usado)_"
+                } else {
+                    ""
+                };
+                vec![format!(
+                    "{}- **{}** `{}`{}",
+                    "  ".repeat(node.depth),
+                    node.entry.name,
+                    node.entry.path.display(),
+                    last_used_marker
+                )]
+            }
+            TreeFormat::Dot => {
+                let style = if node.is_last_used {
+                    ", style=bold"
+                } else {
+                    ""
+                };
+                let mut lines = vec![format!(
+                    "  \"{}\" [label=\"{}\"{}];",
+                    node.uuid,
+                    dot_escape(&node.qualified_name),
+                    style
+                )];
+                if let Some(parent) = node.parent {
+                    lines.push(format!("  \"{}\" -> \"{}\";", parent, node.uuid));
+                }
+                lines
+            }
+        }
+    }
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Un nodo en el recorrido, con lo que cualquier formato necesita para dibujarlo.
+struct TreeNode<'a> {
+    uuid: Uuid,
+    entry: &'a IndexEntry,
+    qualified_name: String,
+    /// El padre dentro del árbol dibujado (`None` para la raíz del dibujo).
+    parent: Option<Uuid>,
+    depth: usize,
+    /// Prefijo de conectores de los ancestros (solo lo usa el formato ASCII).
+    prefix: String,
+    is_last: bool,
+    /// El nodo pedido con `axes <ctx> tree`, que se dibuja como raíz.
+    is_start: bool,
+    is_last_used: bool,
+}
+
+/// Muestra el árbol de todos los proyectos registrados (o del subárbol de `start_node_uuid`)
+/// en el formato indicado.
+/// Si se pasa `visible`, solo se dibujan los nodos de ese conjunto (ver `with_ancestors`).
+pub fn display_project_tree(
+    index: &GlobalIndex,
+    start_node_uuid: Option<Uuid>,
+    visible: Option<&HashSet<Uuid>>,
+    format: TreeFormat,
+) {
     if index.projects.is_empty() {
         println!("\nNo hay proyectos registrados. Usa 'axes init <nombre>' para empezar.");
         return;
     }
 
-    // 1. Construir el mapa de relaciones (sin cambios)
-    let mut children_map: HashMap<Option<Uuid>, Vec<(Uuid, &IndexEntry)>> = HashMap::new();
-    for (uuid, entry) in &index.projects {
-        children_map
-            .entry(entry.parent)
-            .or_default()
-            .push((*uuid, entry));
-    }
-    for children in children_map.values_mut() {
-        children.sort_by_key(|(_, entry)| &entry.name);
+    // 1. Construir el mapa de relaciones, podando los nodos no visibles
+    let mut children_map = build_children_map(index);
+    if let Some(visible) = visible {
+        for children in children_map.values_mut() {
+            children.retain(|(uuid, _)| visible.contains(uuid));
+        }
     }
 
     // 2. Determinar el punto de inicio
+    let mut lines = format.header(start_node_uuid.is_none());
     if let Some(start_uuid) = start_node_uuid {
         // Empezar desde un nodo específico
-        if let Some(start_entry) = index.projects.get(&start_uuid) {
-            // Imprimir el nodo de inicio como si fuera una raíz (sin prefijo ni conector)
-            let last_used_marker = if index.last_used == Some(start_uuid) {
-                " (**)"
-            } else {
-                ""
-            };
-            println!(
-                "{} [{}] {}",
-                start_entry.name,
-                start_entry.path.display(),
-                last_used_marker
-            );
-
-            // Imprimir sus hijos
-            if let Some(children) = children_map.get(&Some(start_uuid)) {
-                for (i, (child_uuid, child_entry)) in children.iter().enumerate() {
-                    let is_last_child = i == children.len() - 1;
-                    print_node(
-                        *child_uuid,
-                        child_entry,
-                        index,
-                        &children_map,
-                        "",
-                        is_last_child,
-                    );
-                }
-            }
-        } else {
+        let Some(start_entry) = index.projects.get(&start_uuid) else {
             println!("\nError: El proyecto de inicio especificado no fue encontrado en el índice.");
-        }
+            return;
+        };
+        let qualified_name = index_manager::build_qualified_name(start_uuid, index)
+            .unwrap_or_else(|_| start_entry.name.clone());
+        let start = TreeNode {
+            uuid: start_uuid,
+            entry: start_entry,
+            qualified_name,
+            parent: None,
+            depth: 0,
+            prefix: String::new(),
+            is_last: true,
+            is_start: true,
+            is_last_used: index.last_used == Some(start_uuid),
+        };
+        render_subtree(&start, index, &children_map, format, &mut lines);
     } else {
         // Comportamiento por defecto: empezar desde las raíces (`global`)
-        if let Some(roots) = children_map.get(&None) {
-            println!("\nÁrbol de Proyectos Registrados:");
-            for (i, (uuid, root_entry)) in roots.iter().enumerate() {
-                let is_last = i == roots.len() - 1;
-                print_node(*uuid, root_entry, index, &children_map, "", is_last);
-            }
-        } else {
+        let Some(roots) = children_map.get(&None) else {
             println!(
                 "\nAdvertencia: No se encontraron proyectos raíz, pero hay proyectos registrados."
             );
             println!("Esto puede indicar un índice corrupto.");
+            return;
+        };
+        for (i, (uuid, root_entry)) in roots.iter().enumerate() {
+            let root = TreeNode {
+                uuid: *uuid,
+                entry: root_entry,
+                qualified_name: root_entry.name.clone(),
+                parent: None,
+                depth: 0,
+                prefix: String::new(),
+                is_last: i == roots.len() - 1,
+                is_start: false,
+                is_last_used: index.last_used == Some(*uuid),
+            };
+            render_subtree(&root, index, &children_map, format, &mut lines);
         }
     }
+    lines.extend(format.footer());
+
+    for line in lines {
+        println!("{}", line);
+    }
 }
 
-/// Función recursiva para imprimir un nodo del árbol y sus descendientes.
-fn print_node(
-    uuid: Uuid,
-    entry: &IndexEntry,
+/// Recorre en profundidad desde `node`, dibujando cada nodo con `format`.
+fn render_subtree(
+    node: &TreeNode,
     index: &GlobalIndex,
-    children_map: &HashMap<Option<Uuid>, Vec<(Uuid, &IndexEntry)>>,
-    prefix: &str,
-    is_last: bool,
+    children_map: &ChildrenMap,
+    format: TreeFormat,
+    lines: &mut Vec<String>,
 ) {
-    let connector = if is_last { "└─" } else { "├─" };
+    lines.extend(format.render_node(node));
 
-    // Comprobar si es el último proyecto usado globalmente
-    let last_used_marker = if index.last_used == Some(uuid) {
-        " (**)"
+    let Some(children) = children_map.get(&Some(node.uuid)) else {
+        return;
+    };
+    // Los hijos del nodo de inicio cuelgan directamente de él, sin sangría extra.
+    let child_prefix = if node.is_start {
+        String::new()
     } else {
-        ""
+        format!("{}{}", node.prefix, indent(node.is_last))
     };
+    for (i, (child_uuid, child_entry)) in children.iter().enumerate() {
+        let child = TreeNode {
+            uuid: *child_uuid,
+            entry: child_entry,
+            qualified_name: format!("{}/{}", node.qualified_name, child_entry.name),
+            parent: Some(node.uuid),
+            depth: node.depth + 1,
+            prefix: child_prefix.clone(),
+            is_last: i == children.len() - 1,
+            is_start: false,
+            is_last_used: index.last_used == Some(*child_uuid),
+        };
+        render_subtree(&child, index, children_map, format, lines);
+    }
+}
+
+/// El árbol de proyectos (o el subárbol de `start_node_uuid`) como JSON, para `tree --json`:
+/// una lista de raíces, cada una con sus `children` anidados. `created` y `modified` van en
+/// segundos Unix (`null` si el índice no las tiene).
+pub fn project_tree_json(
+    index: &GlobalIndex,
+    start_node_uuid: Option<Uuid>,
+    visible: Option<&HashSet<Uuid>>,
+) -> serde_json::Value {
+    let mut children_map = build_children_map(index);
+    if let Some(visible) = visible {
+        for children in children_map.values_mut() {
+            children.retain(|(uuid, _)| visible.contains(uuid));
+        }
+    }
+
+    let roots: Vec<(Uuid, &IndexEntry, String)> = match start_node_uuid {
+        Some(start_uuid) => index
+            .projects
+            .get(&start_uuid)
+            .map(|entry| {
+                let qualified_name = index_manager::build_qualified_name(start_uuid, index)
+                    .unwrap_or_else(|_| entry.name.clone());
+                (start_uuid, entry, qualified_name)
+            })
+            .into_iter()
+            .collect(),
+        None => children_map
+            .get(&None)
+            .map(|roots| {
+                roots
+                    .iter()
+                    .map(|(uuid, entry)| (*uuid, *entry, entry.name.clone()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    serde_json::Value::Array(
+        roots
+            .into_iter()
+            .map(|(uuid, entry, qualified_name)| {
+                node_json(uuid, entry, qualified_name, index, &children_map)
+            })
+            .collect(),
+    )
+}
+
+fn node_json(
+    uuid: Uuid,
+    entry: &IndexEntry,
+    qualified_name: String,
+    index: &GlobalIndex,
+    children_map: &ChildrenMap,
+) -> serde_json::Value {
+    let children: Vec<serde_json::Value> = children_map
+        .get(&Some(uuid))
+        .map(|children| {
+            children
+                .iter()
+                .map(|(child_uuid, child_entry)| {
+                    let child_name = format!("{}/{}", qualified_name, child_entry.name);
+                    node_json(*child_uuid, child_entry, child_name, index, children_map)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    serde_json::json!({
+        "uuid": uuid,
+        "name": entry.name,
+        "qualified_name": qualified_name,
+        "path": entry.path,
+        "created": entry.created,
+        "modified": entry.modified,
+        "last_used": index.last_used == Some(uuid),
+        "children": children,
+    })
+}
+
+/// Amplía un conjunto de proyectos con todos sus ancestros, para que un árbol podado
+/// siga mostrando el camino desde la raíz hasta cada coincidencia.
+pub fn with_ancestors(index: &GlobalIndex, uuids: impl IntoIterator<Item = Uuid>) -> HashSet<Uuid> {
+    let mut visible = HashSet::new();
+    for uuid in uuids {
+        let mut current = Some(uuid);
+        while let Some(current_uuid) = current {
+            if !visible.insert(current_uuid) {
+                break; // Ya visitado (o ciclo): sus ancestros ya están incluidos.
+            }
+            current = index.projects.get(&current_uuid).and_then(|e| e.parent);
+        }
+    }
+    visible
+}
+
+type ChildrenMap<'a> = HashMap<Option<Uuid>, Vec<(Uuid, &'a IndexEntry)>>;
+
+/// Agrupa los proyectos por padre, con los hijos ordenados por nombre.
+fn build_children_map(index: &GlobalIndex) -> ChildrenMap<'_> {
+    let mut children_map: ChildrenMap = HashMap::new();
+    for (uuid, entry) in &index.projects {
+        children_map
+            .entry(entry.parent)
+            .or_default()
+            .push((*uuid, entry));
+    }
+    for children in children_map.values_mut() {
+        children.sort_by_key(|(_, entry)| &entry.name);
+    }
+    children_map
+}
 
-    println!(
-        "{}{}{} [{}] {}",
-        prefix,
-        connector,
-        entry.name,
-        entry.path.display(),
-        last_used_marker
-    );
+/// Aplana el árbol de proyectos en una lista de `(UUID, etiqueta)` en orden de recorrido.
+/// Las etiquetas llevan los mismos conectores que `display_project_tree`, para usarlas
+/// en selectores interactivos.
+pub fn build_tree_items(index: &GlobalIndex) -> Vec<(Uuid, String)> {
+    let children_map = build_children_map(index);
+    let mut items = Vec::new();
+    if let Some(roots) = children_map.get(&None) {
+        for (i, (uuid, entry)) in roots.iter().enumerate() {
+            collect_tree_items(
+                *uuid,
+                entry,
+                &children_map,
+                "",
+                i == roots.len() - 1,
+                &mut items,
+            );
+        }
+    }
+    items
+}
 
-    // Preparar el prefijo para los hijos de este nodo
-    let child_prefix = format!("{}{}", prefix, if is_last { "   " } else { "│  " });
+fn collect_tree_items(
+    uuid: Uuid,
+    entry: &IndexEntry,
+    children_map: &ChildrenMap,
+    prefix: &str,
+    is_last: bool,
+    items: &mut Vec<(Uuid, String)>,
+) {
+    items.push((uuid, format!("{}{}{}", prefix, branch(is_last), entry.name)));
 
-    // Recursión sobre los hijos
+    let child_prefix = format!("{}{}", prefix, indent(is_last));
     if let Some(children) = children_map.get(&Some(uuid)) {
         for (i, (child_uuid, child_entry)) in children.iter().enumerate() {
-            let is_last_child = i == children.len() - 1;
-            print_node(
+            collect_tree_items(
                 *child_uuid,
                 child_entry,
-                index,
                 children_map,
                 &child_prefix,
-                is_last_child,
+                i == children.len() - 1,
+                items,
             );
         }
     }
 }
+
+/// Conector de un nodo del árbol con su padre; en ASCII si no hay colores (`NO_COLOR`).
+fn branch(is_last: bool) -> &'static str {
+    match (output::use_color(), is_last) {
+        (true, true) => "└─",
+        (true, false) => "├─",
+        (false, true) => "`-",
+        (false, false) => "|-",
+    }
+}
+
+/// Sangría bajo un nodo: continúa la línea vertical si el nodo tiene hermanos debajo.
+fn indent(is_last: bool) -> &'static str {
+    match (output::use_color(), is_last) {
+        (_, true) => "   ",
+        (true, false) => "│  ",
+        (false, false) => "|  ",
+    }
+}
@@ -1,19 +1,29 @@
 // src/core/index_manager.rs
 
-use crate::constants::PROJECT_REF_FILENAME;
-use crate::core::paths;
-use crate::models::{GlobalIndex, IndexEntry, ProjectRef};
+use crate::constants::{
+    AXES_DIR, CHILDREN_CACHE_FILENAME, CONFIG_CACHE_FILENAME, PROJECT_REF_FILENAME,
+};
+use crate::core::{config_resolver, context_resolver, paths};
+use crate::models::{ChildCache, GlobalIndex, IndexEntry, LegacyGlobalIndex, ProjectRef};
 use std::collections::HashSet;
 use std::error::Error;
 use std::io::ErrorKind;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::{fs, path::Path, path::PathBuf};
 use thiserror::Error;
 use uuid::Uuid;
 
-use crate::constants::GLOBAL_INDEX_FILENAME;
+use crate::constants::{
+    GLOBAL_INDEX_FILENAME, GLOBAL_INDEX_MAGIC, GLOBAL_INDEX_SCHEMA_VERSION,
+    GLOBAL_LAST_USED_FILENAME, LEGACY_INDEX_FILENAME,
+};
 
 pub const GLOBAL_PROJECT_UUID: Uuid = Uuid::nil();
 
+/// Tiempo máximo que se espera por el bloqueo del índice antes de rendirse.
+const INDEX_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const INDEX_LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
 #[derive(Error, Debug)]
 pub enum IndexError {
     #[error("Error de Ficheros: {0}")]
@@ -37,16 +47,51 @@ pub enum IndexError {
     },
     #[error("El proyecto con UUID '{uuid}' no fue encontrado en el índice global.")]
     ProjectNotFoundInIndex { uuid: Uuid },
+    #[error(
+        "Ciclo en la cadena de padres: el proyecto '{uuid}' llega de nuevo a '{cycle_node_uuid}' al subir por sus ancestros."
+    )]
+    ParentCycle { uuid: Uuid, cycle_node_uuid: Uuid },
     #[error(
         "Dependencia circular detectada: el proyecto '{cycle_node_uuid}' ya es un ancestro de la ruta del nuevo padre. No se puede establecer este enlace."
     )]
     CircularDependency { cycle_node_uuid: Uuid },
+    #[error(
+        "Otro proceso de axes tiene bloqueado el índice global ('{path}'). Inténtalo de nuevo en unos segundos."
+    )]
+    IndexLocked { path: String },
+    #[error("Patrón de nombre inválido: {0}")]
+    InvalidPattern(#[from] glob::PatternError),
+    #[error("La ruta '{path}' ya está registrada por otro proyecto.")]
+    PathAlreadyRegistered { path: String },
+    #[error("El nombre del proyecto no puede estar vacío.")]
+    EmptyName,
+    #[error("El nombre '{name}' no puede contener '/' o '\\'.")]
+    NameHasPathSeparator { name: String },
+    #[error("El nombre '{name}' es reservado y no puede usarse para un proyecto.")]
+    ReservedName { name: String },
+    #[error("No se pudo leer el índice antiguo '{path}': {source}")]
+    LegacyIndexParse {
+        path: String,
+        source: toml::de::Error,
+    },
+    #[error(
+        "El índice global usa el formato {found}, pero esta versión de axes solo entiende hasta el {supported}. Actualiza axes."
+    )]
+    UnsupportedIndexVersion { found: u32, supported: u32 },
 }
 
+/// Nombres que tienen un significado especial al resolver contextos.
+const RESERVED_PROJECT_NAMES: &[&str] = &["global", ".", "..", "*", "_", "**"];
+
 type IndexResult<T> = Result<T, IndexError>;
 
 /// Carga el índice global y asegura que la entrada para el proyecto 'global' exista.
+/// Si solo existe un índice del formato antiguo (`index.toml`), lo migra primero.
 pub fn load_and_ensure_global_project() -> IndexResult<GlobalIndex> {
+    let config_dir = paths::get_axes_config_dir()?;
+    let legacy_path = config_dir.join(LEGACY_INDEX_FILENAME);
+    let needs_migration = !config_dir.join(GLOBAL_INDEX_FILENAME).exists() && legacy_path.exists();
+
     let mut index = load_global_index_internal()?;
     if let std::collections::hash_map::Entry::Vacant(e) = index.projects.entry(GLOBAL_PROJECT_UUID)
     {
@@ -57,6 +102,8 @@ pub fn load_and_ensure_global_project() -> IndexResult<GlobalIndex> {
             name: "global".to_string(),
             path: config_dir.clone(), // Clonar para usarla después
             parent: None,
+            created: Some(unix_now()),
+            modified: None,
         };
         e.insert(global_entry.clone());
 
@@ -91,9 +138,176 @@ pub fn load_and_ensure_global_project() -> IndexResult<GlobalIndex> {
         // Guardar el índice actualizado.
         save_global_index(&index)?;
     }
+
+    if needs_migration {
+        migrate_legacy_index(&mut index, &legacy_path)?;
+    }
     Ok(index)
 }
 
+/// Importa los proyectos de un `index.toml` antiguo (nombres -> rutas, sin jerarquía) como
+/// hijos de `global`, con UUIDs nuevos. Acepta tanto `[projects] nombre = "ruta"` como
+/// `[projects.nombre] path = "ruta"`. El archivo antiguo se conserva como `index.toml.bak`.
+fn migrate_legacy_index(index: &mut GlobalIndex, legacy_path: &Path) -> IndexResult<()> {
+    let content = fs::read_to_string(legacy_path)?;
+    let table: toml::Table =
+        toml::from_str(config_resolver::strip_bom(&content)).map_err(|source| {
+            IndexError::LegacyIndexParse {
+                path: legacy_path.display().to_string(),
+                source,
+            }
+        })?;
+    let projects = match table.get("projects") {
+        Some(toml::Value::Table(projects)) => projects,
+        _ => &table,
+    };
+
+    log::warn!(
+        "Encontrado un índice antiguo en '{}'. Migrando {} proyecto(s)...",
+        legacy_path.display(),
+        projects.len()
+    );
+    for (name, value) in projects {
+        let path = match value {
+            toml::Value::String(path) => path.as_str(),
+            toml::Value::Table(project) => match project.get("path") {
+                Some(toml::Value::String(path)) => path.as_str(),
+                _ => {
+                    log::warn!("Proyecto antiguo '{}' sin ruta: se omite.", name);
+                    continue;
+                }
+            },
+            _ => {
+                log::warn!("Entrada antigua '{}' no reconocida: se omite.", name);
+                continue;
+            }
+        };
+
+        if let Err(e) = validate_project_name(name) {
+            log::warn!("Proyecto antiguo '{}' omitido: {}", name, e);
+            continue;
+        }
+        // Como al registrar, se guarda la ruta canonicalizada (si el directorio existe).
+        let path = PathBuf::from(path);
+        let path = dunce::canonicalize(&path).unwrap_or(path);
+        let (uuid, _) = match add_project_to_index(index, name.clone(), path.clone(), None) {
+            Ok(added) => added,
+            Err(e) => {
+                log::warn!("Proyecto antiguo '{}' omitido: {}", name, e);
+                continue;
+            }
+        };
+        // Si la carpeta ya no existe, `axes doctor` la señalará como ruta inexistente.
+        if path.is_dir() {
+            let project_ref = ProjectRef {
+                self_uuid: uuid,
+                parent_uuid: Some(GLOBAL_PROJECT_UUID),
+                name: name.clone(),
+            };
+            write_project_ref(&path, &project_ref)?;
+
+            // El formato antiguo no tenía `axes.toml`: se crea el de `axes init`.
+            let config_path = path
+                .join(crate::constants::AXES_DIR)
+                .join(crate::constants::PROJECT_CONFIG_FILENAME);
+            if !config_path.exists() {
+                let defaults = crate::models::ProjectConfig::new();
+                fs::write(
+                    config_path,
+                    crate::models::ProjectConfig::default_toml(
+                        &defaults.description.unwrap_or_default(),
+                        &defaults.version.unwrap_or_default(),
+                    ),
+                )?;
+            }
+        }
+    }
+
+    save_global_index(index)?;
+    invalidate_child_caches(index, [GLOBAL_PROJECT_UUID]);
+    let mut backup_name = legacy_path.as_os_str().to_owned();
+    backup_name.push(".bak");
+    fs::rename(legacy_path, PathBuf::from(backup_name))?;
+    Ok(())
+}
+
+/// Guardia de bloqueo exclusivo sobre el índice global.
+/// El bloqueo se libera al hacer `drop` (y el SO lo libera si el proceso muere).
+pub struct IndexLock {
+    file: fs::File,
+}
+
+impl Drop for IndexLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+/// Adquiere un bloqueo consultivo sobre el índice global.
+/// Debe mantenerse vivo durante toda la secuencia cargar-modificar-guardar.
+pub fn lock_global_index() -> IndexResult<IndexLock> {
+    let lock_path = paths::get_global_index_lock_path()?;
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+
+    let deadline = Instant::now() + INDEX_LOCK_TIMEOUT;
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(IndexLock { file }),
+            Err(fs::TryLockError::WouldBlock) => {
+                if Instant::now() >= deadline {
+                    return Err(IndexError::IndexLocked {
+                        path: lock_path.display().to_string(),
+                    });
+                }
+                log::debug!("El índice global está bloqueado. Esperando...");
+                std::thread::sleep(INDEX_LOCK_RETRY_INTERVAL);
+            }
+            Err(fs::TryLockError::Error(e)) => return Err(IndexError::Io(e)),
+        }
+    }
+}
+
+/// Comprueba que `name` puede usarse como nombre de proyecto: no vacío, sin separadores
+/// de ruta y sin ser una palabra reservada de la sintaxis de contextos.
+pub fn validate_project_name(name: &str) -> IndexResult<()> {
+    if name.trim().is_empty() {
+        return Err(IndexError::EmptyName);
+    }
+    if name.contains('/') || name.contains('\\') {
+        return Err(IndexError::NameHasPathSeparator {
+            name: name.to_string(),
+        });
+    }
+    if RESERVED_PROJECT_NAMES.contains(&name.to_lowercase().as_str()) {
+        return Err(IndexError::ReservedName {
+            name: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Normaliza una ruta para guardarla en el índice o compararla con sus entradas.
+/// En Windows quita el prefijo verbatim `\\?\` (el que añade `std::fs::canonicalize`)
+/// cuando es seguro; en el resto de sistemas no la modifica.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    dunce::simplified(path).to_path_buf()
+}
+
+/// Indica si una entrada del índice apunta exactamente a `path`.
+/// Ambas rutas se normalizan, así que da igual si alguna se guardó en formato verbatim.
+pub fn entry_path_eq(entry: &IndexEntry, path: &Path) -> bool {
+    dunce::simplified(&entry.path) == dunce::simplified(path)
+}
+
+/// Indica si `path` está dentro de la ruta de una entrada del índice (o es la misma).
+pub fn entry_path_contains(entry: &IndexEntry, path: &Path) -> bool {
+    dunce::simplified(path).starts_with(dunce::simplified(&entry.path))
+}
+
 /// Añade una nueva entrada de proyecto al índice.
 pub fn add_project_to_index(
     index: &mut GlobalIndex,
@@ -103,11 +317,15 @@ pub fn add_project_to_index(
 ) -> IndexResult<(Uuid, IndexEntry)> {
     let final_parent_uuid = parent_uuid.unwrap_or(GLOBAL_PROJECT_UUID);
 
+    // Con `case_insensitive_names`, `API` y `api` no podrían distinguirse al resolverlos,
+    // así que tampoco pueden ser hermanos.
+    let case_insensitive = context_resolver::case_insensitive_names_enabled(index);
     let name_exists = index.projects.values().any(|entry| {
         if name == "global" {
             false
         } else {
-            entry.parent == Some(final_parent_uuid) && entry.name == name
+            entry.parent == Some(final_parent_uuid)
+                && context_resolver::names_match(&entry.name, &name, case_insensitive)
         }
     });
 
@@ -118,31 +336,101 @@ pub fn add_project_to_index(
     let new_uuid = Uuid::new_v4();
     let new_entry = IndexEntry {
         name,
-        path,
+        path: normalize_path(&path),
         parent: Some(final_parent_uuid),
+        created: Some(unix_now()),
+        modified: None,
     };
 
     index.projects.insert(new_uuid, new_entry.clone());
     Ok((new_uuid, new_entry))
 }
 
+/// Lee el índice global tal cual está en disco, sin crearlo ni añadir el proyecto `global`.
+/// Si no existe, devuelve un índice vacío.
+pub fn read_global_index() -> IndexResult<GlobalIndex> {
+    load_global_index_internal()
+}
+
 fn load_global_index_internal() -> IndexResult<GlobalIndex> {
-    let path = paths::get_axes_config_dir()?.join(GLOBAL_INDEX_FILENAME);
-    if !path.exists() {
-        return Ok(GlobalIndex::default());
+    let config_dir = paths::get_axes_config_dir()?;
+    let path = config_dir.join(GLOBAL_INDEX_FILENAME);
+    let mut index = if path.exists() {
+        decode_global_index(&fs::read(&path)?)?
+    } else {
+        GlobalIndex::default()
+    };
+    // El `last_used` vive en su propio archivo; el guardado en `index.bin` es solo el de
+    // índices anteriores a ese archivo.
+    if let Some(last_used) = read_global_last_used(&config_dir) {
+        index.last_used = Some(last_used);
+    }
+    Ok(index)
+}
+
+/// Decodifica `index.bin`: cabecera, versión y el índice. Un índice sin cabecera es del
+/// formato anterior a las fechas de `IndexEntry`; se convierte dejándolas sin valor y se
+/// reescribe con el formato nuevo en el siguiente guardado.
+fn decode_global_index(bytes: &[u8]) -> IndexResult<GlobalIndex> {
+    let Some(body) = bytes.strip_prefix(GLOBAL_INDEX_MAGIC.as_slice()) else {
+        log::debug!("Índice global sin cabecera: se lee con el formato anterior.");
+        let (legacy, _): (LegacyGlobalIndex, usize) =
+            bincode::serde::decode_from_slice(bytes, bincode::config::standard())?;
+        return Ok(legacy.into());
+    };
+    let (version, read): (u32, usize) =
+        bincode::serde::decode_from_slice(body, bincode::config::standard())?;
+    if version > GLOBAL_INDEX_SCHEMA_VERSION {
+        return Err(IndexError::UnsupportedIndexVersion {
+            found: version,
+            supported: GLOBAL_INDEX_SCHEMA_VERSION,
+        });
     }
-    let bytes = fs::read(&path)?;
-    // Usar bincode para deserializar desde los bytes
     let (index, _): (GlobalIndex, usize) =
-        bincode::serde::decode_from_slice(&bytes, bincode::config::standard())?;
+        bincode::serde::decode_from_slice(&body[read..], bincode::config::standard())?;
     Ok(index)
 }
 
+fn read_global_last_used(config_dir: &Path) -> Option<Uuid> {
+    let path = config_dir.join(GLOBAL_LAST_USED_FILENAME);
+    let bytes = fs::read(&path).ok()?;
+    match bincode::serde::decode_from_slice::<Uuid, _>(&bytes, bincode::config::standard()) {
+        Ok((uuid, _)) => Some(uuid),
+        Err(e) => {
+            log::warn!(
+                "El archivo de último usado '{}' está corrupto y se ignorará: {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Guarda el `last_used` global sin tocar `index.bin`. No necesita el bloqueo del índice:
+/// se escribe en un temporal y se renombra, así que un lector nunca ve un archivo a medias.
+pub fn save_global_last_used(uuid: Uuid) -> IndexResult<()> {
+    let config_dir = paths::get_axes_config_dir()?;
+    let bytes = bincode::serde::encode_to_vec(uuid, bincode::config::standard())?;
+    let mut temp = tempfile::NamedTempFile::new_in(&config_dir)?;
+    std::io::Write::write_all(&mut temp, &bytes)?;
+    temp.persist(config_dir.join(GLOBAL_LAST_USED_FILENAME))
+        .map_err(|e| e.error)?;
+    Ok(())
+}
+
 /// Guarda el índice global en el disco.
 pub fn save_global_index(index: &GlobalIndex) -> IndexResult<()> {
     let path = paths::get_axes_config_dir()?.join(GLOBAL_INDEX_FILENAME);
-    // Usar bincode para serializar a bytes
-    let bytes = bincode::serde::encode_to_vec(index, bincode::config::standard())?;
+    let mut bytes = GLOBAL_INDEX_MAGIC.to_vec();
+    bytes.extend(bincode::serde::encode_to_vec(
+        GLOBAL_INDEX_SCHEMA_VERSION,
+        bincode::config::standard(),
+    )?);
+    bytes.extend(bincode::serde::encode_to_vec(
+        index,
+        bincode::config::standard(),
+    )?);
     fs::write(path, bytes)?;
     Ok(())
 }
@@ -198,6 +486,7 @@ pub fn rename_project(
     // El `else` con el `Err` es una capa extra de seguridad, aunque teóricamente inalcanzable.
     if let Some(entry_to_modify) = index.projects.get_mut(&target_uuid) {
         entry_to_modify.name = new_name.to_string();
+        touch(entry_to_modify);
     } else {
         return Err(IndexError::ProjectNotFoundInIndex { uuid: target_uuid });
     }
@@ -243,8 +532,10 @@ pub fn find_cycle_from_node(
     Ok(None) // El bucle nunca se ejecutó (start_node_uuid era None) o no se encontró ciclo.
 }
 
-pub fn link_project(
-    index: &mut GlobalIndex,
+/// Comprueba, sin modificar el índice, si `project_to_move_uuid` puede pasar a ser hijo de
+/// `new_parent_uuid`: que no se cree un ciclo ni choque con un hermano del mismo nombre.
+pub fn validate_link(
+    index: &GlobalIndex,
     project_to_move_uuid: Uuid,
     new_parent_uuid: Uuid,
 ) -> IndexResult<()> {
@@ -295,9 +586,21 @@ pub fn link_project(
         });
     }
 
+    Ok(())
+}
+
+pub fn link_project(
+    index: &mut GlobalIndex,
+    project_to_move_uuid: Uuid,
+    new_parent_uuid: Uuid,
+) -> IndexResult<()> {
+    // 1-3. Anti-ciclos y anti-colisión de nombres de hermano.
+    validate_link(index, project_to_move_uuid, new_parent_uuid)?;
+
     // 4. Si todas las validaciones pasan, realizar el cambio en el índice real.
     if let Some(entry_to_modify) = index.projects.get_mut(&project_to_move_uuid) {
         entry_to_modify.parent = Some(new_parent_uuid);
+        touch(entry_to_modify);
     } else {
         return Err(IndexError::ProjectNotFoundInIndex {
             uuid: project_to_move_uuid,
@@ -307,6 +610,167 @@ pub fn link_project(
     Ok(())
 }
 
+// Diagnóstico
+
+/// Un problema de consistencia detectado en el índice global.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexIssue {
+    /// La entrada apunta a un padre que no existe en el índice.
+    BrokenParentLink {
+        uuid: Uuid,
+        missing_parent_uuid: Uuid,
+    },
+    /// La ruta registrada ya no existe en el disco.
+    MissingPath { uuid: Uuid, path: PathBuf },
+    /// La cadena de padres de la entrada contiene un ciclo.
+    Cycle { uuid: Uuid, cycle_node_uuid: Uuid },
+}
+
+/// Recorre el índice en busca de padres rotos, rutas inexistentes y ciclos.
+/// No modifica nada; los problemas se devuelven ordenados por nombre de proyecto.
+pub fn diagnose_index(index: &GlobalIndex) -> Vec<IndexIssue> {
+    let mut entries: Vec<(&Uuid, &IndexEntry)> = index.projects.iter().collect();
+    entries.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+
+    let mut issues = Vec::new();
+    let mut reported_cycles = HashSet::new();
+
+    for (uuid, entry) in entries {
+        if let Some(parent_uuid) = entry.parent
+            && !index.projects.contains_key(&parent_uuid)
+        {
+            issues.push(IndexIssue::BrokenParentLink {
+                uuid: *uuid,
+                missing_parent_uuid: parent_uuid,
+            });
+        }
+
+        if !is_project_path_valid(&entry.path) {
+            issues.push(IndexIssue::MissingPath {
+                uuid: *uuid,
+                path: entry.path.clone(),
+            });
+        }
+
+        if let Ok(Some(cycle_node_uuid)) = find_cycle_from_node(*uuid, index)
+            && reported_cycles.insert(cycle_node_uuid)
+        {
+            issues.push(IndexIssue::Cycle {
+                uuid: *uuid,
+                cycle_node_uuid,
+            });
+        }
+    }
+
+    issues
+}
+
+/// Comprueba que la ruta de un proyecto todavía existe (canonicalizándola).
+fn is_project_path_valid(path: &Path) -> bool {
+    dunce::canonicalize(path).is_ok()
+}
+
+/// Devuelve los proyectos cuya ruta registrada ya no existe en el disco.
+pub fn find_missing_project_paths(index: &GlobalIndex) -> Vec<(Uuid, PathBuf)> {
+    let mut missing: Vec<(Uuid, PathBuf)> = index
+        .projects
+        .iter()
+        .filter(|(_, entry)| !is_project_path_valid(&entry.path))
+        .map(|(uuid, entry)| (*uuid, entry.path.clone()))
+        .collect();
+    missing.sort_by(|(_, a), (_, b)| a.cmp(b));
+    missing
+}
+
+/// Actualiza la ruta de un proyecto a una nueva ubicación existente.
+/// Devuelve la ruta canonicalizada que se guardó en el índice.
+pub fn relocate_project(
+    index: &mut GlobalIndex,
+    target_uuid: Uuid,
+    new_path: &Path,
+) -> IndexResult<PathBuf> {
+    let canonical_path = dunce::canonicalize(new_path)?;
+
+    let path_taken = index
+        .projects
+        .iter()
+        .any(|(uuid, entry)| *uuid != target_uuid && entry_path_eq(entry, &canonical_path));
+    if path_taken {
+        return Err(IndexError::PathAlreadyRegistered {
+            path: canonical_path.display().to_string(),
+        });
+    }
+
+    let entry = index
+        .projects
+        .get(&target_uuid)
+        .ok_or(IndexError::ProjectNotFoundInIndex { uuid: target_uuid })?;
+
+    // Re-validar que ningún hermano colisione con el nombre de la entrada.
+    let sibling_name_exists = index.projects.iter().any(|(uuid, other)| {
+        *uuid != target_uuid && other.parent == entry.parent && other.name == entry.name
+    });
+    if sibling_name_exists {
+        return Err(IndexError::NameAlreadyExists {
+            name: entry.name.clone(),
+        });
+    }
+
+    if let Some(entry_to_modify) = index.projects.get_mut(&target_uuid) {
+        entry_to_modify.path = canonical_path.clone();
+        touch(entry_to_modify);
+    }
+    Ok(canonical_path)
+}
+
+/// Reescribe las rutas de los proyectos anidados físicamente bajo `old_root` para que
+/// cuelguen de `new_root`. Se usa tras mover un directorio de proyecto completo.
+/// Devuelve los UUIDs actualizados.
+pub fn rebase_nested_project_paths(
+    index: &mut GlobalIndex,
+    old_root: &Path,
+    new_root: &Path,
+) -> Vec<Uuid> {
+    let mut rebased = Vec::new();
+    for (uuid, entry) in index.projects.iter_mut() {
+        if let Ok(relative) = entry.path.strip_prefix(old_root)
+            && !relative.as_os_str().is_empty()
+        {
+            entry.path = new_root.join(relative);
+            touch(entry);
+            rebased.push(*uuid);
+        }
+    }
+    rebased
+}
+
+/// Aplica la reparación sugerida para un problema del índice.
+/// Los padres rotos y los ciclos se re-parentan a `global`; las rutas inexistentes se
+/// desregistran (re-parentando a sus hijos). Devuelve `false` si ya no había nada que reparar.
+pub fn repair_index_issue(index: &mut GlobalIndex, issue: &IndexIssue) -> bool {
+    match issue {
+        IndexIssue::BrokenParentLink { uuid, .. } => match index.projects.get_mut(uuid) {
+            Some(entry) => {
+                entry.parent = Some(GLOBAL_PROJECT_UUID);
+                touch(entry);
+                true
+            }
+            None => false,
+        },
+        IndexIssue::Cycle {
+            cycle_node_uuid, ..
+        } => match index.projects.get_mut(cycle_node_uuid) {
+            Some(entry) => {
+                entry.parent = Some(GLOBAL_PROJECT_UUID);
+                touch(entry);
+                true
+            }
+            None => false,
+        },
+        IndexIssue::MissingPath { uuid, .. } => remove_from_index(index, &[*uuid], true) > 0,
+    }
+}
+
 //Utils
 
 /// Lee el `project_ref.bin` de un proyecto. Si no existe, lo crea a partir del índice global.
@@ -369,6 +833,7 @@ pub fn delete_project_entry(index: &mut GlobalIndex, target_uuid: Uuid) -> Optio
     for child_uuid in children_to_reparent {
         if let Some(child_entry) = index.projects.get_mut(&child_uuid) {
             child_entry.parent = Some(GLOBAL_PROJECT_UUID);
+            touch(child_entry);
         }
     }
 
@@ -376,6 +841,53 @@ pub fn delete_project_entry(index: &mut GlobalIndex, target_uuid: Uuid) -> Optio
     index.projects.remove(&target_uuid)
 }
 
+/// Calcula, sin modificar el índice, cómo cambiarían los nombres cualificados del proyecto
+/// y de todos sus descendientes si se renombrara a `new_name`. Aplica las mismas
+/// validaciones que `rename_project`. Devuelve `(uuid, nombre_actual, nombre_nuevo)`,
+/// con el propio proyecto primero y los descendientes ordenados por nombre.
+pub fn preview_rename(
+    index: &GlobalIndex,
+    target_uuid: Uuid,
+    new_name: &str,
+) -> IndexResult<Vec<(Uuid, String, String)>> {
+    let mut renamed = index.clone();
+    rename_project(&mut renamed, target_uuid, new_name)?;
+    qualified_name_changes(index, &renamed, target_uuid)
+}
+
+/// Como `preview_rename`, pero para enlazar el proyecto bajo `new_parent_uuid`. Aplica las
+/// mismas validaciones que `link_project` (ciclos y hermanos con el mismo nombre).
+pub fn preview_link(
+    index: &GlobalIndex,
+    target_uuid: Uuid,
+    new_parent_uuid: Uuid,
+) -> IndexResult<Vec<(Uuid, String, String)>> {
+    let mut linked = index.clone();
+    link_project(&mut linked, target_uuid, new_parent_uuid)?;
+    qualified_name_changes(index, &linked, target_uuid)
+}
+
+/// Nombres cualificados de `target_uuid` y sus descendientes antes y después de un cambio.
+fn qualified_name_changes(
+    before: &GlobalIndex,
+    after: &GlobalIndex,
+    target_uuid: Uuid,
+) -> IndexResult<Vec<(Uuid, String, String)>> {
+    let mut descendants = get_all_descendants(before, target_uuid);
+    descendants.sort_by_cached_key(|uuid| build_qualified_name(*uuid, before).unwrap_or_default());
+
+    std::iter::once(target_uuid)
+        .chain(descendants)
+        .map(|uuid| {
+            Ok((
+                uuid,
+                build_qualified_name(uuid, before)?,
+                build_qualified_name(uuid, after)?,
+            ))
+        })
+        .collect()
+}
+
 /// Recolecta todos los UUIDs descendientes de un nodo inicial.
 pub fn get_all_descendants(index: &GlobalIndex, start_uuid: Uuid) -> Vec<Uuid> {
     let mut descendants = Vec::new();
@@ -395,6 +907,24 @@ pub fn get_all_descendants(index: &GlobalIndex, start_uuid: Uuid) -> Vec<Uuid> {
     descendants
 }
 
+/// Los padres cuyos hijos cambian al quitar `uuids_to_remove`: los que los pierden y, al
+/// re-parentar los huérfanos, `global`. Se calcula antes de `remove_from_index`, para
+/// pasárselo a `invalidate_child_caches` tras guardar el índice.
+pub fn parents_affected_by_removal(
+    index: &GlobalIndex,
+    uuids_to_remove: &[Uuid],
+    should_reparent_orphans: bool,
+) -> HashSet<Uuid> {
+    let mut affected_parents: HashSet<Uuid> = uuids_to_remove
+        .iter()
+        .filter_map(|uuid| index.projects.get(uuid).and_then(|e| e.parent))
+        .collect();
+    if should_reparent_orphans {
+        affected_parents.insert(GLOBAL_PROJECT_UUID);
+    }
+    affected_parents
+}
+
 pub fn remove_from_index(
     index: &mut GlobalIndex,
     uuids_to_remove: &[Uuid],
@@ -418,6 +948,7 @@ pub fn remove_from_index(
         for child_uuid in children_to_reparent {
             if let Some(child_entry) = index.projects.get_mut(&child_uuid) {
                 child_entry.parent = Some(GLOBAL_PROJECT_UUID);
+                touch(child_entry);
             }
         }
     }
@@ -434,27 +965,165 @@ pub fn remove_from_index(
     removed_count
 }
 
+/// Ruta del caché de hijos (`nombre -> UUID`) de un proyecto padre, dentro de su `.axes/`.
+fn child_cache_path(parent_entry: &IndexEntry) -> PathBuf {
+    parent_entry
+        .path
+        .join(AXES_DIR)
+        .join(CHILDREN_CACHE_FILENAME)
+}
+
+/// Lee el caché de hijos de un padre. Un caché ausente o corrupto se trata como inexistente.
+pub fn read_child_cache(parent_entry: &IndexEntry) -> Option<ChildCache> {
+    let bytes = fs::read(child_cache_path(parent_entry)).ok()?;
+    match bincode::serde::decode_from_slice(&bytes, bincode::config::standard()) {
+        Ok((cache, _)) => Some(cache),
+        Err(e) => {
+            log::warn!(
+                "Caché de hijos de '{}' corrupto, se regenerará. (Error: {})",
+                parent_entry.name,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Regenera desde el índice el caché de hijos de `parent_uuid` y lo guarda. Como es solo
+/// un caché, un fallo al escribirlo se registra pero no se propaga.
+pub fn write_child_cache(index: &GlobalIndex, parent_uuid: Uuid) -> ChildCache {
+    let cache = ChildCache {
+        children: index
+            .projects
+            .iter()
+            .filter(|(_, e)| e.parent == Some(parent_uuid))
+            .map(|(uuid, e)| (e.name.clone(), *uuid))
+            .collect(),
+    };
+    if let Some(parent_entry) = index.projects.get(&parent_uuid) {
+        let path = child_cache_path(parent_entry);
+        let result = bincode::serde::encode_to_vec(&cache, bincode::config::standard())
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| {
+                fs::create_dir_all(path.parent().unwrap_or(&path))
+                    .and_then(|_| fs::write(&path, bytes))
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            log::debug!(
+                "No se pudo escribir el caché de hijos en '{}': {}",
+                path.display(),
+                e
+            );
+        }
+    }
+    cache
+}
+
+/// Borra el caché de hijos de cada padre de `parent_uuids`; se regenerará en la próxima
+/// resolución que lo necesite. Se llama tras guardar un índice cuyos hijos cambiaron: las
+/// funciones que modifican el índice en memoria no tocan el disco, ya que las vistas previas
+/// (`--dry-run`) las aplican sobre una copia.
+pub fn invalidate_child_caches(index: &GlobalIndex, parent_uuids: impl IntoIterator<Item = Uuid>) {
+    for parent_uuid in parent_uuids {
+        if let Some(parent_entry) = index.projects.get(&parent_uuid) {
+            let _ = fs::remove_file(child_cache_path(parent_entry));
+        }
+    }
+}
+
+/// Borra el caché de configuración de `start_uuid` y de todos sus descendientes. Tras un
+/// cambio de nombre cualificado (ej. `rename`) esos cachés describen un nombre que ya no
+/// existe; así la próxima resolución los reconstruye sin depender de que lo detecte la
+/// comprobación del nombre. Devuelve cuántos cachés se borraron.
+pub fn invalidate_config_caches(index: &GlobalIndex, start_uuid: Uuid) -> usize {
+    std::iter::once(start_uuid)
+        .chain(get_all_descendants(index, start_uuid))
+        .filter_map(|uuid| index.projects.get(&uuid))
+        .filter(|entry| {
+            fs::remove_file(entry.path.join(AXES_DIR).join(CONFIG_CACHE_FILENAME)).is_ok()
+        })
+        .count()
+}
+
+/// La hora actual en segundos Unix, el formato de `created`/`modified` en el índice.
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Anota un cambio estructural (nombre, padre o ruta) en la entrada.
+fn touch(entry: &mut IndexEntry) {
+    entry.modified = Some(unix_now());
+}
+
 /// Reconstruye el nombre cualificado de un proyecto subiendo por el árbol de padres.
-pub fn build_qualified_name(start_uuid: Uuid, index: &GlobalIndex) -> Option<String> {
+pub fn build_qualified_name(start_uuid: Uuid, index: &GlobalIndex) -> IndexResult<String> {
+    // Detectar primero los ciclos: si no, subir por los padres no terminaría nunca.
+    if let Some(cycle_node_uuid) = find_cycle_from_node(start_uuid, index)? {
+        return Err(IndexError::ParentCycle {
+            uuid: start_uuid,
+            cycle_node_uuid,
+        });
+    }
+
     let mut parts = Vec::new();
+    let mut child_uuid = None;
     let mut current_uuid = Some(start_uuid);
 
     while let Some(uuid) = current_uuid {
-        if let Some(entry) = index.projects.get(&uuid) {
-            parts.push(entry.name.clone());
-            current_uuid = entry.parent;
-            // Si el padre es `None`, hemos llegado a la raíz del árbol de `axes`.
-            if entry.parent.is_none() {
-                break;
-            }
-        } else {
+        let Some(entry) = index.projects.get(&uuid) else {
             // Enlace roto, no se puede construir el nombre completo.
-            return None;
-        }
+            return Err(match child_uuid {
+                Some(child_uuid) => IndexError::BrokenParentLink {
+                    child_uuid,
+                    missing_parent_uuid: uuid,
+                },
+                None => IndexError::ProjectNotFoundInIndex { uuid },
+            });
+        };
+        parts.push(entry.name.clone());
+        // Si el padre es `None`, hemos llegado a la raíz del árbol de `axes`.
+        child_uuid = Some(uuid);
+        current_uuid = entry.parent;
     }
 
     parts.reverse();
-    Some(parts.join("/"))
+    Ok(parts.join("/"))
+}
+
+/// Devuelve los proyectos cuyo nombre cualificado coincide con un patrón glob, ordenados
+/// por nombre. El patrón se compara con el nombre completo (`global/a/b`) y también sin el
+/// prefijo `global/`, que es como se escriben los contextos. `*` no cruza `/`; `**` sí.
+pub fn find_projects_by_pattern(
+    index: &GlobalIndex,
+    pattern: &str,
+    ignore_case: bool,
+) -> IndexResult<Vec<(Uuid, String)>> {
+    let pattern = glob::Pattern::new(pattern)?;
+    let options = glob::MatchOptions {
+        case_sensitive: !ignore_case,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+
+    let mut matches: Vec<(Uuid, String)> = index
+        .projects
+        .keys()
+        .filter_map(|uuid| {
+            build_qualified_name(*uuid, index)
+                .ok()
+                .map(|name| (*uuid, name))
+        })
+        .filter(|(_, name)| {
+            let context_name = name.strip_prefix("global/").unwrap_or(name);
+            pattern.matches_with(name, options) || pattern.matches_with(context_name, options)
+        })
+        .collect();
+    matches.sort_by(|(_, a), (_, b)| a.cmp(b));
+    Ok(matches)
 }
 
 // Alias Handlers
@@ -473,3 +1142,192 @@ pub fn remove_alias(index: &mut GlobalIndex, name: &str) -> bool {
     }
     index.aliases.remove(name).is_some()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestIndex;
+    use std::collections::HashMap;
+
+    #[test]
+    fn project_names_reject_reserved_words_and_separators() {
+        for name in RESERVED_PROJECT_NAMES.iter().copied().chain(["GLOBAL"]) {
+            assert!(
+                matches!(
+                    validate_project_name(name),
+                    Err(IndexError::ReservedName { .. })
+                ),
+                "'{}' debería ser reservado",
+                name
+            );
+        }
+        for name in ["app/api", "app\\api", "/", "\\"] {
+            assert!(matches!(
+                validate_project_name(name),
+                Err(IndexError::NameHasPathSeparator { .. })
+            ));
+        }
+        assert!(matches!(
+            validate_project_name("  "),
+            Err(IndexError::EmptyName)
+        ));
+        assert!(validate_project_name("mi-app_2").is_ok());
+    }
+
+    #[test]
+    fn qualified_name_reports_missing_parent() {
+        let mut test = TestIndex::new();
+        let app = test.add("app", GLOBAL_PROJECT_UUID, "app");
+        assert_eq!(
+            build_qualified_name(app, &test.index).unwrap(),
+            "global/app"
+        );
+
+        let missing = Uuid::new_v4();
+        let orphan = test.add("orphan", missing, "orphan");
+        assert!(matches!(
+            build_qualified_name(orphan, &test.index),
+            Err(IndexError::BrokenParentLink { child_uuid, missing_parent_uuid })
+                if child_uuid == orphan && missing_parent_uuid == missing
+        ));
+    }
+
+    #[test]
+    fn qualified_name_reports_parent_cycle() {
+        let mut test = TestIndex::new();
+        let a = test.add("a", GLOBAL_PROJECT_UUID, "a");
+        let b = test.add("b", a, "a/b");
+        test.index.projects.get_mut(&a).unwrap().parent = Some(b);
+
+        assert!(matches!(
+            build_qualified_name(b, &test.index),
+            Err(IndexError::ParentCycle { uuid, .. }) if uuid == b
+        ));
+    }
+
+    #[test]
+    fn legacy_index_is_migrated_under_global() {
+        let mut test = TestIndex::new();
+        let app_path = test.root.join("app");
+        fs::create_dir_all(&app_path).unwrap();
+        let legacy_path = test.root.join(LEGACY_INDEX_FILENAME);
+        fs::write(
+            &legacy_path,
+            format!(
+                "[projects]\napp = '{}'\n\"a/b\" = '{}'\n\n[projects.docs]\npath = '{}'\n",
+                app_path.join("..").join("app").display(),
+                app_path.display(),
+                test.root.join("docs").display()
+            ),
+        )
+        .unwrap();
+
+        migrate_legacy_index(&mut test.index, &legacy_path).unwrap();
+
+        let migrated: HashMap<&str, (&Uuid, &IndexEntry)> = test
+            .index
+            .projects
+            .iter()
+            .filter(|(uuid, _)| **uuid != GLOBAL_PROJECT_UUID)
+            .map(|(uuid, entry)| (entry.name.as_str(), (uuid, entry)))
+            .collect();
+        assert_eq!(migrated.len(), 2, "'a/b' no es un nombre válido");
+        let (app_uuid, app) = migrated["app"];
+        assert_eq!(app.parent, Some(GLOBAL_PROJECT_UUID));
+        // La ruta `app/../app` se guarda canonicalizada, como al registrar.
+        assert_eq!(app.path, dunce::canonicalize(&app_path).unwrap());
+        assert_eq!(migrated["docs"].1.parent, Some(GLOBAL_PROJECT_UUID));
+
+        // El proyecto que existe en disco recibe su `project_ref.bin` y un `axes.toml`.
+        assert_eq!(read_project_ref(&app_path).unwrap().self_uuid, *app_uuid);
+        assert!(
+            app_path
+                .join(crate::constants::AXES_DIR)
+                .join(crate::constants::PROJECT_CONFIG_FILENAME)
+                .is_file()
+        );
+
+        assert!(!legacy_path.exists());
+        assert!(test.root.join("index.toml.bak").is_file());
+    }
+
+    #[test]
+    fn rename_clears_descendant_config_caches() {
+        let mut test = TestIndex::new();
+        let app = test.add("app", GLOBAL_PROJECT_UUID, "app");
+        let api = test.add("api", app, "app/api");
+        let v1 = test.add("v1", api, "app/api/v1");
+        let docs = test.add("docs", GLOBAL_PROJECT_UUID, "docs");
+        let cache_paths: HashMap<Uuid, PathBuf> = [app, api, v1, docs]
+            .into_iter()
+            .map(|uuid| {
+                let entry = &test.index.projects[&uuid];
+                (uuid, entry.path.join(AXES_DIR).join(CONFIG_CACHE_FILENAME))
+            })
+            .collect();
+        let cache_path = |uuid: &Uuid| &cache_paths[uuid];
+        for path in cache_paths.values() {
+            fs::write(path, b"cache").unwrap();
+        }
+
+        rename_project(&mut test.index, app, "web").unwrap();
+        assert_eq!(invalidate_config_caches(&test.index, app), 3);
+
+        for uuid in [app, api, v1] {
+            assert!(!cache_path(&uuid).exists());
+        }
+        // El caché de un proyecto fuera del subárbol renombrado se conserva.
+        assert!(cache_path(&docs).exists());
+    }
+
+    #[test]
+    fn sibling_names_differing_in_case_depend_on_the_option() {
+        let mut test = TestIndex::new();
+        test.add("api", GLOBAL_PROJECT_UUID, "api");
+        let path = test.root.join("API");
+        assert!(add_project_to_index(&mut test.index, "API".to_string(), path, None).is_ok());
+
+        let mut test = TestIndex::with_global_config("[options]\ncase_insensitive_names = true\n");
+        test.add("api", GLOBAL_PROJECT_UUID, "api");
+        let path = test.root.join("API");
+        assert!(matches!(
+            add_project_to_index(&mut test.index, "API".to_string(), path, None),
+            Err(IndexError::NameAlreadyExists { name }) if name == "API"
+        ));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn verbatim_entry_paths_match_their_plain_form() {
+        let entry = IndexEntry {
+            name: "app".to_string(),
+            path: PathBuf::from(r"\\?\C:\proyectos\app"),
+            parent: Some(GLOBAL_PROJECT_UUID),
+            created: None,
+            modified: None,
+        };
+        assert_eq!(
+            normalize_path(&entry.path),
+            PathBuf::from(r"C:\proyectos\app")
+        );
+
+        assert!(entry_path_eq(&entry, Path::new(r"C:\proyectos\app")));
+        assert!(entry_path_contains(&entry, Path::new(r"C:\proyectos\app")));
+        assert!(entry_path_contains(
+            &entry,
+            Path::new(r"C:\proyectos\app\src")
+        ));
+        assert!(!entry_path_contains(&entry, Path::new(r"C:\proyectos\api")));
+
+        // Y al revés: una entrada sin prefijo frente a una ruta verbatim.
+        let plain = IndexEntry {
+            path: PathBuf::from(r"C:\proyectos\app"),
+            ..entry
+        };
+        assert!(entry_path_eq(&plain, Path::new(r"\\?\C:\proyectos\app")));
+        assert!(entry_path_contains(
+            &plain,
+            Path::new(r"\\?\C:\proyectos\app\src")
+        ));
+    }
+}
@@ -1,13 +1,33 @@
 // src/core/interpolator.rs
 
-use crate::models::ResolvedConfig;
+use crate::models::{ResolvedConfig, Runnable};
 use dunce;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Convierte un comando de `[options]` (ej. una entrada de `open_with`) en el comando final.
+/// Los comandos de opciones nunca reciben parámetros de `run`; `{path}` apunta a `path`.
+pub fn option_command(config: &ResolvedConfig, command: &str, path: &Path) -> String {
+    Interpolator::new(config, &[])
+        .with_path(path)
+        .interpolate(command)
+}
+
+/// Igual que `option_command` para las opciones con varios comandos (`at_start`, `at_exit`),
+/// descartando los vacíos. `{path}` es la raíz del proyecto.
+pub fn option_commands(config: &ResolvedConfig, runnable: &Runnable) -> Vec<String> {
+    runnable
+        .commands()
+        .iter()
+        .filter(|command| !command.trim().is_empty())
+        .map(|command| option_command(config, command, &config.project_root))
+        .collect()
+}
 
 pub struct Interpolator<'a> {
     config: &'a ResolvedConfig,
     params: &'a [String],
     owner_root: &'a PathBuf,
+    current_path: &'a Path,
 }
 
 impl<'a> Interpolator<'a> {
@@ -16,9 +36,16 @@ impl<'a> Interpolator<'a> {
             config,
             params,
             owner_root: &config.project_root,
+            current_path: &config.project_root,
         }
     }
 
+    /// Cambia el valor de `{path}` (por defecto, la raíz del proyecto).
+    pub fn with_path(mut self, path: &'a Path) -> Self {
+        self.current_path = path;
+        self
+    }
+
     /// Interpola una cadena de texto, reemplazando todos los tokens conocidos
     /// en un orden de precedencia fijo para garantizar la seguridad y la previsibilidad.
     pub fn interpolate(&self, input: &str) -> String {
@@ -27,12 +54,22 @@ impl<'a> Interpolator<'a> {
         self.interpolate_params(&pass2)
     }
 
-    /// Reemplaza tokens reservados y metadatos del proyecto.
+    /// Reemplaza tokens reservados y metadatos del proyecto: `{uuid}` (forma con guiones),
+    /// `{name}`, `{qualified_name}`, `{root}`, `{path}` y `{version}`.
     fn interpolate_reserved(&self, input: &str) -> String {
         let mut result = input.to_string();
 
-        result = result.replace("{uuid}", &self.config.uuid.to_string());
-        result = result.replace("{name}", &self.config.qualified_name);
+        result = result.replace("{uuid}", &self.config.uuid.hyphenated().to_string());
+        // `{name}` es solo el último segmento (`api`); `{qualified_name}`, el nombre completo
+        // (`global/backend/api`).
+        let short_name = self
+            .config
+            .qualified_name
+            .rsplit('/')
+            .next()
+            .unwrap_or(&self.config.qualified_name);
+        result = result.replace("{name}", short_name);
+        result = result.replace("{qualified_name}", &self.config.qualified_name);
 
         // **NUEVA LÓGICA DE FORMATEO DE RUTA**
         // `dunce::canonicalize` hace lo mismo que `std::fs::canonicalize`
@@ -42,7 +79,7 @@ impl<'a> Interpolator<'a> {
 
         // El `owner_root` también necesita ser limpiado.
         let owner_root_clean = dunce::simplified(self.owner_root).to_string_lossy();
-        let current_path_clean = dunce::simplified(&self.config.project_root).to_string_lossy();
+        let current_path_clean = dunce::simplified(self.current_path).to_string_lossy();
 
         result = result.replace("{root}", &owner_root_clean);
         result = result.replace("{path}", &current_path_clean);
@@ -87,3 +124,42 @@ impl<'a> Interpolator<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn config(uuid: Uuid, qualified_name: &str) -> ResolvedConfig {
+        ResolvedConfig {
+            uuid,
+            qualified_name: qualified_name.to_string(),
+            project_root: PathBuf::from("/proyectos/app/api"),
+            version: None,
+            description: None,
+            commands: Default::default(),
+            options: Default::default(),
+            vars: Default::default(),
+            env: Default::default(),
+        }
+    }
+
+    #[test]
+    fn uuid_token_uses_the_hyphenated_form() {
+        let uuid = Uuid::parse_str("6f1c2a3b4d5e4f60a1b2c3d4e5f60718").unwrap();
+        let config = config(uuid, "global/app/api");
+        assert_eq!(
+            Interpolator::new(&config, &[]).interpolate("docker run --name axes-{uuid}"),
+            "docker run --name axes-6f1c2a3b-4d5e-4f60-a1b2-c3d4e5f60718"
+        );
+    }
+
+    #[test]
+    fn name_is_the_last_segment_of_the_qualified_name() {
+        let config = config(Uuid::new_v4(), "global/app/api");
+        assert_eq!(
+            Interpolator::new(&config, &[]).interpolate("{name} en {qualified_name}"),
+            "api en global/app/api"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 // src/core/mod.rs
 
+pub mod config_editor;
 pub mod config_resolver;
 pub mod context_resolver;
 pub mod graph_display;
@@ -7,3 +8,5 @@ pub mod index_manager;
 pub mod interpolator;
 pub mod onboarding_manager;
 pub mod paths;
+pub mod runner;
+pub mod templates;
@@ -2,9 +2,9 @@
 
 use crate::core::index_manager::{self, GLOBAL_PROJECT_UUID};
 use crate::models::{GlobalIndex, IndexEntry, ProjectRef};
-use dialoguer::{
-    Confirm, Error as DialoguerError, Input, MultiSelect, Select, theme::ColorfulTheme,
-};
+use crate::output;
+use crate::prompt;
+use dialoguer::{Error as DialoguerError, Input, MultiSelect, Select};
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -18,6 +18,8 @@ pub enum OnboardingError {
     Index(#[from] crate::core::index_manager::IndexError),
     #[error("Error de Interfaz de Usuario: {0}")]
     Dialoguer(#[from] DialoguerError),
+    #[error("{0}")]
+    Prompt(#[from] prompt::PromptError),
     #[error("El directorio '{0}' no parece ser un proyecto de `axes` (falta '.axes/axes.toml').")]
     NotAnAxesProject(String),
     #[error("La ruta del proyecto ya está registrada con un UUID diferente.")]
@@ -34,6 +36,17 @@ pub struct OnboardingOptions {
     pub suggested_parent_uuid: Option<Uuid>,
 }
 
+/// Directorios que nunca se recorren al buscar proyectos (además de los ocultos).
+const SKIPPED_SCAN_DIRS: &[&str] = &["node_modules", "target"];
+
+/// Resultado de un registro recursivo (`axes register --recursive`).
+#[derive(Debug, Default)]
+pub struct RecursiveRegisterSummary {
+    pub added: Vec<PathBuf>,
+    pub skipped: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
 /// La función principal de la máquina de estados de onboarding.
 pub fn register_project(
     path: &Path,
@@ -53,7 +66,11 @@ pub fn register_project(
     }
 
     // Comprobar si la RUTA ya está registrada. Si es así, saltar al escaneo de hijos.
-    if let Some((uuid, _)) = index.projects.iter().find(|(_, e)| e.path == project_root) {
+    if let Some((uuid, _)) = index
+        .projects
+        .iter()
+        .find(|(_, e)| index_manager::entry_path_eq(e, &project_root))
+    {
         println!("Este proyecto ya está registrado. Pasando al escaneo de hijos...");
         scan_and_register_children(&project_root, *uuid, index, options)?;
         return Ok(());
@@ -72,13 +89,124 @@ pub fn register_project(
     };
 
     // Obtener el UUID recién registrado para el escaneo de hijos.
-    if let Some((uuid, _)) = index.projects.iter().find(|(_, e)| e.path == project_root) {
+    if let Some((uuid, _)) = index
+        .projects
+        .iter()
+        .find(|(_, e)| index_manager::entry_path_eq(e, &project_root))
+    {
         scan_and_register_children(&project_root, *uuid, index, options)?;
     }
 
     Ok(())
 }
 
+/// Recorre `root` buscando directorios con `.axes/axes.toml` y registra cada uno.
+/// Los proyectos se registran en orden de anidamiento (padres antes que hijos), y el padre
+/// de cada uno se infiere del proyecto registrado más cercano que lo contiene.
+/// Los ya registrados (misma ruta canónica) se omiten; un fallo no detiene al resto.
+pub fn register_projects_recursive(
+    root: &Path,
+    max_depth: Option<usize>,
+    index: &mut GlobalIndex,
+    autosolve: bool,
+) -> OnboardingResult<RecursiveRegisterSummary> {
+    let root = dunce::canonicalize(root)?;
+    let mut summary = RecursiveRegisterSummary::default();
+
+    for project_root in discover_projects(&root, max_depth)? {
+        if index
+            .projects
+            .values()
+            .any(|e| index_manager::entry_path_eq(e, &project_root))
+        {
+            summary.skipped.push(project_root);
+            continue;
+        }
+
+        println!(
+            "\n--- Analizando proyecto en: {} ---",
+            project_root.display()
+        );
+        let inferred_parent = find_enclosing_project(&project_root, index);
+        let options = OnboardingOptions {
+            autosolve,
+            suggested_parent_uuid: Some(inferred_parent),
+        };
+
+        let result = match index_manager::read_project_ref(&project_root) {
+            Ok(mut pref) => {
+                // Si el padre guardado ya no existe, se usa el inferido por el anidamiento.
+                if !pref
+                    .parent_uuid
+                    .is_some_and(|p| index.projects.contains_key(&p))
+                {
+                    pref.parent_uuid = Some(inferred_parent);
+                }
+                handle_registration_with_ref(project_root.clone(), pref, index, &options)
+            }
+            Err(_) => handle_registration_without_ref(project_root.clone(), index, &options),
+        };
+
+        match result {
+            Ok(()) => summary.added.push(project_root),
+            Err(e) => {
+                eprintln!("Advertencia: no se pudo registrar el proyecto: {}", e);
+                summary.failed.push((project_root, e.to_string()));
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Busca proyectos de `axes` a partir de `root` (incluido), en anchura, de modo que un
+/// directorio siempre aparece antes que sus subdirectorios. `max_depth = 0` solo mira `root`.
+fn discover_projects(root: &Path, max_depth: Option<usize>) -> OnboardingResult<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    let mut queue = std::collections::VecDeque::from([(root.to_path_buf(), 0usize)]);
+
+    while let Some((dir, depth)) = queue.pop_front() {
+        if dir.join(".axes/axes.toml").exists() {
+            found.push(dir.clone());
+        }
+        if max_depth.is_some_and(|max| depth >= max) {
+            continue;
+        }
+
+        let mut subdirs = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            // No se siguen enlaces simbólicos para evitar bucles.
+            if !file_type.is_dir() || name.starts_with('.') || SKIPPED_SCAN_DIRS.contains(&&*name) {
+                continue;
+            }
+            subdirs.push(entry.path());
+        }
+        subdirs.sort();
+        queue.extend(subdirs.into_iter().map(|p| (p, depth + 1)));
+    }
+
+    Ok(found)
+}
+
+/// Devuelve el proyecto registrado más profundo cuya ruta contiene a `path`, o `global`.
+fn find_enclosing_project(path: &Path, index: &GlobalIndex) -> Uuid {
+    index
+        .projects
+        .iter()
+        .filter(|(uuid, e)| {
+            **uuid != GLOBAL_PROJECT_UUID
+                && !index_manager::entry_path_eq(e, path)
+                && index_manager::entry_path_contains(e, path)
+        })
+        .max_by_key(|(_, e)| e.path.components().count())
+        .map(|(uuid, _)| *uuid)
+        .unwrap_or(GLOBAL_PROJECT_UUID)
+}
+
 fn handle_registration_with_ref(
     project_root: PathBuf,
     mut pref: ProjectRef, // Hacerla mutable para poder corregirla
@@ -97,15 +225,11 @@ fn handle_registration_with_ref(
                 existing_entry.path.display().to_string(),
             ));
         }
-        let prompt = format!(
+        let question = format!(
             "El UUID de este proyecto ya está registrado en otra ruta ({}). ¿Actualizar la ruta a la ubicación actual?",
             existing_entry.path.display()
         );
-        if !Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(prompt)
-            .default(true)
-            .interact()?
-        {
+        if !prompt::confirm(&question, true)? {
             return Err(OnboardingError::Cancelled);
         }
         // El usuario aceptó, la actualización de la ruta se hará al final.
@@ -147,16 +271,21 @@ fn handle_registration_with_ref(
             "Conflicto de nombre: El padre seleccionado ya tiene un hijo llamado '{}'.",
             pref.name
         );
-        pref.name = Input::with_theme(&ColorfulTheme::default())
+        prompt::require_interactive("nuevo nombre para el proyecto")?;
+        pref.name = Input::with_theme(&*output::theme())
             .with_prompt("Por favor, introduce un nuevo nombre para este proyecto")
             .interact_text()?;
     }
 
     // 4. Registrar/Actualizar en el índice
+    // Una entrada que ya existía conserva su fecha de registro; el cambio cuenta como modificación.
+    let existing = index.projects.get(&pref.self_uuid);
     let final_entry = IndexEntry {
         name: pref.name.clone(),
         path: project_root.clone(),
         parent: pref.parent_uuid,
+        created: existing.map_or(Some(index_manager::unix_now()), |entry| entry.created),
+        modified: existing.map(|_| index_manager::unix_now()),
     };
     index.projects.insert(pref.self_uuid, final_entry);
 
@@ -201,7 +330,8 @@ fn handle_registration_without_ref(
     } else {
         println!("No se encontró una referencia local. Se solicitarán los detalles.");
 
-        let name: String = Input::with_theme(&ColorfulTheme::default())
+        prompt::require_interactive("nombre para este proyecto")?;
+        let name: String = Input::with_theme(&*output::theme())
             .with_prompt("Nombre para este proyecto:")
             .default(
                 project_root
@@ -240,10 +370,10 @@ fn scan_and_register_children(
     options: &OnboardingOptions,
 ) -> OnboardingResult<()> {
     if !options.autosolve
-        && !Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("¿Escanear subdirectorios en busca de hijos no registrados?")
-            .default(true)
-            .interact()?
+        && !prompt::confirm(
+            "¿Escanear subdirectorios en busca de hijos no registrados?",
+            true,
+        )?
     {
         return Ok(());
     }
@@ -255,7 +385,11 @@ fn scan_and_register_children(
         let path = entry.path();
         if path.is_dir() && path.join(".axes/axes.toml").exists() {
             // Es un proyecto de axes. ¿Está ya registrado?
-            if !index.projects.values().any(|e| e.path == path) {
+            if !index
+                .projects
+                .values()
+                .any(|e| index_manager::entry_path_eq(e, &path))
+            {
                 unregistered_children.push(path);
             }
         }
@@ -273,7 +407,8 @@ fn scan_and_register_children(
             .iter()
             .map(|p| p.file_name().unwrap().to_string_lossy())
             .collect();
-        let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+        prompt::require_interactive("hijos a registrar; usa `--autosolve`")?;
+        let selections = MultiSelect::with_theme(&*output::theme())
             .with_prompt("Se encontraron los siguientes hijos no registrados. Selecciona cuáles registrar (espacio para marcar, enter para continuar):")
             .items(&child_names)
             .interact()?;
@@ -315,7 +450,8 @@ fn choose_parent(index: &GlobalIndex, suggested_parent: Option<Uuid>) -> Onboard
                 .unwrap()
         });
 
-    let selection = Select::with_theme(&ColorfulTheme::default())
+    prompt::require_interactive("proyecto padre")?;
+    let selection = Select::with_theme(&*output::theme())
         .with_prompt("Selecciona el proyecto padre:")
         .items(&parent_names)
         .default(default_selection)
@@ -1,6 +1,9 @@
 // src/core/paths.rs
 
-use crate::constants::GLOBAL_INDEX_FILENAME; // Usar la nueva constante
+use crate::constants::{
+    GLOBAL_INDEX_FILENAME, GLOBAL_INDEX_LOCK_FILENAME, TEMPLATE_REPOS_DIRNAME,
+    USER_TEMPLATES_DIRNAME,
+};
 use std::fs;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -20,7 +23,7 @@ pub enum PathError {
 /// Devuelve la ruta al directorio de configuración de Axes.
 /// Lo crea si no existe.
 pub fn get_axes_config_dir() -> Result<PathBuf, PathError> {
-    let config_path = dirs::config_dir()
+    let config_path = user_config_dir()
         .ok_or(PathError::ConfigDirNotFound)?
         .join("axes");
 
@@ -33,8 +36,35 @@ pub fn get_axes_config_dir() -> Result<PathBuf, PathError> {
     Ok(config_path)
 }
 
-/// Devuelve la ruta al archivo `index.toml` global.
+#[cfg(not(test))]
+fn user_config_dir() -> Option<PathBuf> {
+    dirs::config_dir()
+}
+
+/// En los tests, un directorio temporal compartido por todo el proceso, para no tocar la
+/// configuración real del usuario.
+#[cfg(test)]
+fn user_config_dir() -> Option<PathBuf> {
+    Some(crate::test_support::config_dir().to_path_buf())
+}
+
+/// Devuelve la ruta al archivo `index.bin` global.
 /// Este es el archivo principal en el directorio de configuración de axes.
 pub fn get_global_index_path() -> Result<PathBuf, PathError> {
     get_axes_config_dir().map(|dir| dir.join(GLOBAL_INDEX_FILENAME))
 }
+
+/// Devuelve la ruta al archivo de bloqueo del índice global.
+pub fn get_global_index_lock_path() -> Result<PathBuf, PathError> {
+    get_axes_config_dir().map(|dir| dir.join(GLOBAL_INDEX_LOCK_FILENAME))
+}
+
+/// Devuelve el directorio de plantillas del usuario (puede no existir).
+pub fn get_user_templates_dir() -> Result<PathBuf, PathError> {
+    get_axes_config_dir().map(|dir| dir.join(USER_TEMPLATES_DIRNAME))
+}
+
+/// Devuelve el directorio donde se guardan los clones de repositorios de plantillas.
+pub fn get_template_repos_dir() -> Result<PathBuf, PathError> {
+    get_axes_config_dir().map(|dir| dir.join(TEMPLATE_REPOS_DIRNAME))
+}
@@ -0,0 +1,672 @@
+// src/core/runner.rs
+
+use crate::core::interpolator::Interpolator;
+use crate::models::{Command as ProjectCommand, PlatformCommand, ResolvedConfig, Runnable};
+use crate::output;
+use crate::status;
+use crate::system::executor::{self, ExecOptions, ExecutionError};
+use serde::{Serialize, Serializer};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum RunError {
+    #[error("Script '{0}' no encontrado en la configuración del proyecto.")]
+    ScriptNotFound(String),
+    #[error(
+        "El script '{0}' no tiene una implementación para el SO actual y no tiene un 'default'."
+    )]
+    NoPlatformVariant(String),
+    #[error("Dependencia circular entre scripts: {}", .0.join(" -> "))]
+    DependencyCycle(Vec<String>),
+    #[error("El script '{script}' depende de '{dependency}', que no está definido.")]
+    MissingDependency { script: String, dependency: String },
+    #[error(transparent)]
+    Execution(#[from] ExecutionError),
+    #[error("{error} Se intentó {attempts} veces.")]
+    RetriesExhausted {
+        attempts: u32,
+        error: ExecutionError,
+    },
+    #[error("La secuencia '{script}' terminó con {failed} paso(s) fallido(s).")]
+    SequenceFailed { script: String, failed: usize },
+    #[error("El hook 'post' del script '{script}' falló.")]
+    PostHookFailed {
+        script: String,
+        #[source]
+        source: Box<RunError>,
+    },
+    #[error("El script '{script}' falló: {main}\nAdemás, su hook 'post' también falló: {post}")]
+    MainAndPostHookFailed {
+        script: String,
+        main: Box<RunError>,
+        post: Box<RunError>,
+    },
+}
+
+impl RunError {
+    /// Código de salida del comando que falló, si lo hay (ver `ExecutionError::exit_code`).
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            RunError::Execution(e) => e.exit_code(),
+            RunError::RetriesExhausted { error, .. } => error.exit_code(),
+            RunError::PostHookFailed { source, .. } => source.exit_code(),
+            _ => None,
+        }
+    }
+}
+
+pub type RunResult<T> = Result<T, RunError>;
+
+/// Fase de un script a la que pertenece un paso ejecutado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepPhase {
+    Pre,
+    Run,
+    Post,
+}
+
+/// Un comando ejecutado: cada paso de una secuencia es una entrada propia.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepReport {
+    /// Script al que pertenece el comando.
+    pub script: String,
+    pub phase: StepPhase,
+    /// El comando ya interpolado.
+    pub command: String,
+    /// `Some(0)` si terminó bien; `None` si no llegó a lanzarse o lo mató una señal.
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub duration: Duration,
+}
+
+/// Resumen de una ejecución: los comandos lanzados, en orden. Si la ejecución falla, el
+/// último paso es el que falló (salvo con `continue_on_error`, que sigue adelante).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunReport {
+    pub steps: Vec<StepReport>,
+}
+
+impl RunReport {
+    /// `true` si ningún paso falló.
+    pub fn success(&self) -> bool {
+        self.steps.iter().all(|step| step.success)
+    }
+
+    /// Tiempo total de los comandos ejecutados.
+    pub fn duration(&self) -> Duration {
+        self.steps.iter().map(|step| step.duration).sum()
+    }
+}
+
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_millis() as u64)
+}
+
+/// Calcula el orden de ejecución de unos scripts y sus `depends_on` (orden topológico),
+/// respetando el orden en que se pidieron. Cada script aparece una sola vez.
+pub fn resolve_script_order(
+    config: &ResolvedConfig,
+    root_scripts: &[String],
+) -> RunResult<Vec<String>> {
+    fn visit(
+        config: &ResolvedConfig,
+        name: &str,
+        stack: &mut Vec<String>,
+        order: &mut Vec<String>,
+    ) -> RunResult<()> {
+        if order.iter().any(|done| done == name) {
+            return Ok(());
+        }
+        if let Some(pos) = stack.iter().position(|pending| pending == name) {
+            let mut cycle = stack[pos..].to_vec();
+            cycle.push(name.to_string());
+            return Err(RunError::DependencyCycle(cycle));
+        }
+
+        let dependencies = match config.commands.get(name) {
+            Some(ProjectCommand::Extended(ext)) => ext.depends_on.as_slice(),
+            Some(_) => &[],
+            None => {
+                return Err(RunError::MissingDependency {
+                    script: stack.last().cloned().unwrap_or_default(),
+                    dependency: name.to_string(),
+                });
+            }
+        };
+
+        stack.push(name.to_string());
+        for dependency in dependencies {
+            visit(config, dependency, stack, order)?;
+        }
+        stack.pop();
+
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    let mut order = Vec::new();
+    for root_script in root_scripts {
+        visit(config, root_script, &mut Vec::new(), &mut order)?;
+    }
+    Ok(order)
+}
+
+/// Obtiene el `Runnable` de un script, eligiendo la variante del SO actual si es multi-plataforma.
+pub fn get_script_runnable(config: &ResolvedConfig, script_key: &str) -> RunResult<Runnable> {
+    let command_def = config
+        .commands
+        .get(script_key)
+        .ok_or_else(|| RunError::ScriptNotFound(script_key.to_string()))?;
+
+    let runnable = match command_def {
+        ProjectCommand::Sequence(s) => Runnable::Sequence(s.clone()),
+        ProjectCommand::Simple(s) => Runnable::Single(s.clone()),
+        ProjectCommand::Extended(ext) => ext.run.clone(),
+        ProjectCommand::Platform(pc) => select_platform_runnable(pc)
+            .map(|(_, runnable)| runnable.clone())
+            .ok_or_else(|| RunError::NoPlatformVariant(script_key.to_string()))?,
+    };
+    Ok(runnable)
+}
+
+/// Elige la variante de un comando multi-plataforma para el SO actual (o `default`).
+/// Devuelve también el nombre de la variante elegida.
+pub fn select_platform_runnable(pc: &PlatformCommand) -> Option<(&'static str, &Runnable)> {
+    platform_runnable_for(pc, std::env::consts::OS)
+}
+
+/// Como `select_platform_runnable`, para el SO `os` (un valor de `std::env::consts::OS`).
+/// Un SO sin campo propio usa `other`; si la variante no está definida, se usa `default`.
+pub fn platform_runnable_for<'a>(
+    pc: &'a PlatformCommand,
+    os: &str,
+) -> Option<(&'static str, &'a Runnable)> {
+    let (variant, runnable) = match os {
+        "windows" => ("windows", &pc.windows),
+        "linux" => ("linux", &pc.linux),
+        "macos" => ("macos", &pc.macos),
+        "freebsd" => ("freebsd", &pc.freebsd),
+        _ => ("other", &pc.other),
+    };
+    runnable
+        .as_ref()
+        .map(|r| (variant, r))
+        .or_else(|| pc.default.as_ref().map(|r| ("default", r)))
+}
+
+/// Indica si un comando puede ejecutarse en el sistema operativo actual.
+/// Solo los comandos multi-plataforma sin variante para este SO (ni `default`) no pueden.
+pub fn is_command_available(command: &ProjectCommand) -> bool {
+    match command {
+        ProjectCommand::Platform(pc) => select_platform_runnable(pc).is_some(),
+        _ => true,
+    }
+}
+
+/// Las líneas de comando, ya interpoladas, que ejecutaría un script (sin sus dependencias):
+/// las de `pre`, las de `run` y las de `post`, un paso de secuencia por línea. Los hooks no
+/// reciben los parámetros. Se quita el prefijo `-` (ignorar errores), que solo entiende axes.
+pub fn script_commands(
+    config: &ResolvedConfig,
+    script_key: &str,
+    params: &[String],
+) -> RunResult<Vec<String>> {
+    let runnable = get_script_runnable(config, script_key)?;
+    let (pre, post) = match config.commands.get(script_key) {
+        Some(ProjectCommand::Extended(ext)) => (ext.pre.clone(), ext.post.clone()),
+        _ => (None, None),
+    };
+    let hook_interpolator = Interpolator::new(config, &[]);
+    let interpolator = Interpolator::new(config, params);
+
+    let lines = |runnable: Runnable, interpolator: &Interpolator| -> Vec<String> {
+        let templates = match runnable {
+            Runnable::Single(template) => vec![template],
+            Runnable::Sequence(templates) => templates,
+        };
+        templates
+            .iter()
+            .map(|template| {
+                let command = interpolator.interpolate(template);
+                let command = command.trim();
+                command
+                    .strip_prefix('-')
+                    .unwrap_or(command)
+                    .trim()
+                    .to_string()
+            })
+            .collect()
+    };
+
+    let mut commands = Vec::new();
+    if let Some(pre) = pre {
+        commands.extend(lines(pre, &hook_interpolator));
+    }
+    commands.extend(lines(runnable, &interpolator));
+    if let Some(post) = post {
+        commands.extend(lines(post, &hook_interpolator));
+    }
+    Ok(commands)
+}
+
+/// Ejecuta un script precedido de sus `depends_on`. Los parámetros solo se pasan al script
+/// pedido, no a sus prerrequisitos. No pide las confirmaciones (`confirm`) de los scripts.
+/// Los comandos lanzados se añaden a `report`, también si la ejecución falla.
+pub fn run_with_dependencies(
+    config: &ResolvedConfig,
+    script_key: &str,
+    params: &[String],
+    report: &mut RunReport,
+) -> RunResult<()> {
+    let requested = [script_key.to_string()];
+    for name in resolve_script_order(config, &requested)? {
+        if name == script_key {
+            run_script(config, &name, params, report)?;
+        } else {
+            status!("\n--- Prerrequisito '{}' ---", name);
+            run_script(config, &name, &[], report)?;
+        }
+    }
+    Ok(())
+}
+
+/// Ejecuta un único script (sin sus dependencias), con sus hooks `pre`/`post` si los tiene.
+/// El hook `post` se ejecuta aunque `run` falle, para permitir tareas de limpieza.
+/// Los comandos lanzados se añaden a `report`.
+pub fn run_script(
+    config: &ResolvedConfig,
+    script_key: &str,
+    params: &[String],
+    report: &mut RunReport,
+) -> RunResult<()> {
+    run_script_in(config, script_key, params, &config.project_root, report)
+}
+
+/// Como `run_script`, pero los comandos (hooks incluidos) se ejecutan en `workdir` en lugar
+/// de la raíz del proyecto. `{root}` sigue apuntando a la raíz.
+pub fn run_script_in(
+    config: &ResolvedConfig,
+    script_key: &str,
+    params: &[String],
+    workdir: &Path,
+    report: &mut RunReport,
+) -> RunResult<()> {
+    // 1. Obtener el `Runnable` de la definición del comando.
+    let runnable_template = get_script_runnable(config, script_key)?;
+    let (pre_hook, post_hook, run_options) = match config.commands.get(script_key) {
+        Some(ProjectCommand::Extended(ext)) => (
+            ext.pre.clone(),
+            ext.post.clone(),
+            RunOptions {
+                continue_on_error: ext.continue_on_error,
+                exec: ExecOptions {
+                    timeout: ext.timeout_secs.map(Duration::from_secs),
+                    force_shell: ext.shell,
+                },
+                retries: ext.retries,
+                retry_delay: Duration::from_secs(ext.retry_delay_secs.unwrap_or(0)),
+            },
+        ),
+        _ => (None, None, RunOptions::default()),
+    };
+
+    // 2. Ejecutar `pre` y el `Runnable`. Los hooks no reciben los parámetros del usuario.
+    let hook_interpolator = Interpolator::new(config, &[]);
+    let interpolator = Interpolator::new(config, params);
+
+    let main_result = match pre_hook {
+        Some(pre) => {
+            status!("\n--- Hook 'pre' de '{}' ---", script_key);
+            execute_runnable(
+                config,
+                (script_key, StepPhase::Pre),
+                pre,
+                &hook_interpolator,
+                workdir,
+                RunOptions::default(),
+                report,
+            )
+        }
+        None => Ok(()),
+    }
+    .and_then(|_| {
+        execute_runnable(
+            config,
+            (script_key, StepPhase::Run),
+            runnable_template,
+            &interpolator,
+            workdir,
+            run_options,
+            report,
+        )
+    });
+
+    // 3. Ejecutar `post` pase lo que pase.
+    let post_result = match post_hook {
+        Some(post) => {
+            status!("\n--- Hook 'post' de '{}' ---", script_key);
+            execute_runnable(
+                config,
+                (script_key, StepPhase::Post),
+                post,
+                &hook_interpolator,
+                workdir,
+                RunOptions::default(),
+                report,
+            )
+        }
+        None => Ok(()),
+    };
+
+    match (main_result, post_result) {
+        (Ok(()), Ok(())) => Ok(()),
+        (Err(main_err), Ok(())) => Err(main_err),
+        (Ok(()), Err(post_err)) => Err(RunError::PostHookFailed {
+            script: script_key.to_string(),
+            source: Box::new(post_err),
+        }),
+        (Err(main_err), Err(post_err)) => Err(RunError::MainAndPostHookFailed {
+            script: script_key.to_string(),
+            main: Box::new(main_err),
+            post: Box::new(post_err),
+        }),
+    }
+}
+
+/// Opciones de ejecución del `run` de un comando extendido.
+#[derive(Debug, Default, Clone, Copy)]
+struct RunOptions {
+    /// En secuencias, ejecuta todos los pasos y falla al final si alguno falló.
+    continue_on_error: bool,
+    /// Opciones que se aplican a cada comando individual.
+    exec: ExecOptions,
+    /// Veces que se reintenta un comando que falla, y la espera entre intentos.
+    retries: u32,
+    retry_delay: Duration,
+}
+
+/// Interpola y ejecuta un `Runnable` (un comando o una secuencia) en `workdir`.
+fn execute_runnable(
+    config: &ResolvedConfig,
+    (script_key, phase): (&str, StepPhase),
+    runnable: Runnable,
+    interpolator: &Interpolator,
+    workdir: &Path,
+    options: RunOptions,
+    report: &mut RunReport,
+) -> RunResult<()> {
+    // Ejecuta un comando y lo anota en el informe, haya ido bien o mal. Con `retries`,
+    // un fallo se reintenta (cada intento es un paso del informe) salvo si fue un Ctrl+C.
+    let mut execute = |command: String| -> RunResult<()> {
+        let max_attempts = options.retries.saturating_add(1);
+        let mut attempt = 1;
+        loop {
+            let started = Instant::now();
+            let result = executor::execute_command_with_options(
+                &command,
+                workdir,
+                &config.env,
+                options.exec,
+            );
+            report.steps.push(StepReport {
+                script: script_key.to_string(),
+                phase,
+                command: command.clone(),
+                exit_code: match &result {
+                    Ok(()) => Some(0),
+                    Err(e) => e.exit_code(),
+                },
+                success: result.is_ok(),
+                duration: started.elapsed(),
+            });
+
+            let error = match result {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+            let interrupted =
+                matches!(error, ExecutionError::Interrupted(_)) || executor::was_interrupted();
+            if attempt >= max_attempts || interrupted {
+                return Err(if attempt > 1 {
+                    RunError::RetriesExhausted {
+                        attempts: attempt,
+                        error,
+                    }
+                } else {
+                    error.into()
+                });
+            }
+
+            eprintln!(
+                "{} Intento {}/{} fallido: {}",
+                output::fail_mark(),
+                attempt,
+                max_attempts,
+                error
+            );
+            if !options.retry_delay.is_zero() {
+                status!("Reintentando en {} s...", options.retry_delay.as_secs());
+                std::thread::sleep(options.retry_delay);
+            }
+            attempt += 1;
+        }
+    };
+
+    match runnable {
+        Runnable::Single(command_template) => {
+            let final_command = interpolator.interpolate(&command_template);
+            status!("\n> {}", final_command);
+            execute(final_command)?;
+        }
+        Runnable::Sequence(command_templates) => {
+            status!(
+                "\nEjecutando secuencia de comandos para '{}'...",
+                script_key
+            );
+            let mut failed_steps: Vec<(usize, String)> = Vec::new();
+            for (i, command_template) in command_templates.iter().enumerate() {
+                let final_command = interpolator.interpolate(command_template);
+                status!(
+                    "\n[{}/{}]> {}",
+                    i + 1,
+                    command_templates.len(),
+                    final_command
+                );
+
+                match execute(final_command.clone()) {
+                    Ok(()) => {}
+                    // Sin `continue_on_error`, el primer fallo detiene la secuencia.
+                    Err(e) if !options.continue_on_error => return Err(e),
+                    Err(e) => {
+                        eprintln!("{} Paso {} falló: {}", output::fail_mark(), i + 1, e);
+                        failed_steps.push((i + 1, final_command));
+                    }
+                }
+            }
+
+            if !failed_steps.is_empty() {
+                println!(
+                    "\nResumen: {} de {} pasos fallaron:",
+                    failed_steps.len(),
+                    command_templates.len()
+                );
+                for (step, command) in &failed_steps {
+                    println!("  [{}] {}", step, command);
+                }
+                return Err(RunError::SequenceFailed {
+                    script: script_key.to_string(),
+                    failed: failed_steps.len(),
+                });
+            }
+            status!("\n{} Secuencia completada con éxito.", output::ok_mark());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    /// Un proyecto en `root` con los `[commands]` de `commands` (en TOML).
+    fn config_with_commands(root: &Path, commands: &str) -> ResolvedConfig {
+        ResolvedConfig {
+            uuid: Uuid::new_v4(),
+            qualified_name: "global/app".to_string(),
+            project_root: root.to_path_buf(),
+            version: None,
+            description: None,
+            commands: toml::from_str(commands).unwrap(),
+            options: Default::default(),
+            vars: HashMap::new(),
+            env: HashMap::new(),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn post_hook_runs_when_run_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_commands(
+            dir.path(),
+            r#"
+            [deploy]
+            run = "false"
+            post = "touch cleaned-up"
+            "#,
+        );
+
+        let mut report = RunReport::default();
+        let result = run_script(&config, "deploy", &[], &mut report);
+
+        assert!(matches!(
+            result,
+            Err(RunError::Execution(
+                ExecutionError::NonZeroExitStatus { .. }
+            ))
+        ));
+        assert!(dir.path().join("cleaned-up").exists());
+        let phases: Vec<(StepPhase, bool)> =
+            report.steps.iter().map(|s| (s.phase, s.success)).collect();
+        assert_eq!(phases, [(StepPhase::Run, false), (StepPhase::Post, true)]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failure_carries_the_child_exit_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_commands(dir.path(), r#"fail = "sh -c 'exit 7'""#);
+
+        let error = run_script(&config, "fail", &[], &mut RunReport::default()).unwrap_err();
+        assert!(matches!(
+            &error,
+            RunError::Execution(ExecutionError::NonZeroExitStatus { code: Some(7), .. })
+        ));
+        assert_eq!(error.exit_code(), Some(7));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commands_run_in_the_given_workdir() {
+        let dir = tempfile::tempdir().unwrap();
+        let workdir = dir.path().join("sub");
+        std::fs::create_dir(&workdir).unwrap();
+        let config = config_with_commands(
+            dir.path(),
+            r#"
+            [mark]
+            pre = "touch pre-here"
+            run = "sh -c 'echo {root} > root.txt'"
+            "#,
+        );
+
+        run_script_in(&config, "mark", &[], &workdir, &mut RunReport::default()).unwrap();
+
+        // Los hooks también se ejecutan en `workdir`, y `{root}` sigue siendo la raíz.
+        assert!(workdir.join("pre-here").exists());
+        assert_eq!(
+            std::fs::read_to_string(workdir.join("root.txt"))
+                .unwrap()
+                .trim(),
+            dir.path().display().to_string()
+        );
+        assert!(!dir.path().join("root.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_command_is_retried_until_it_succeeds() {
+        let dir = tempfile::tempdir().unwrap();
+        // Falla la primera vez (cuando aún no existe `flag`) y funciona la segunda.
+        let config = config_with_commands(
+            dir.path(),
+            r#"
+            [flaky]
+            run = "sh -c 'test -f flag || { touch flag; exit 1; }'"
+            retries = 1
+            "#,
+        );
+
+        let mut report = RunReport::default();
+        run_script(&config, "flaky", &[], &mut report).unwrap();
+
+        let attempts: Vec<(Option<i32>, bool)> = report
+            .steps
+            .iter()
+            .map(|s| (s.exit_code, s.success))
+            .collect();
+        assert_eq!(attempts, [(Some(1), false), (Some(0), true)]);
+    }
+
+    #[test]
+    fn platform_variant_is_chosen_by_os() {
+        let pc: PlatformCommand = toml::from_str(
+            r#"
+            windows = "win"
+            linux = "lin"
+            macos = "mac"
+            freebsd = "bsd"
+            other = "otro"
+            default = "def"
+            "#,
+        )
+        .unwrap();
+        let selected = |os| {
+            platform_runnable_for(&pc, os)
+                .map(|(variant, runnable)| (variant, runnable.commands()[0].as_str()))
+        };
+        assert_eq!(selected("windows"), Some(("windows", "win")));
+        assert_eq!(selected("linux"), Some(("linux", "lin")));
+        assert_eq!(selected("macos"), Some(("macos", "mac")));
+        assert_eq!(selected("freebsd"), Some(("freebsd", "bsd")));
+        assert_eq!(selected("openbsd"), Some(("other", "otro")));
+    }
+
+    #[test]
+    fn missing_platform_variant_falls_back_to_default() {
+        let pc: PlatformCommand = toml::from_str(r#"linux = "lin""#).unwrap();
+        assert!(platform_runnable_for(&pc, "macos").is_none());
+
+        let pc: PlatformCommand = toml::from_str(
+            r#"
+            linux = "lin"
+            default = "def"
+            "#,
+        )
+        .unwrap();
+        for os in ["windows", "macos", "freebsd", "openbsd"] {
+            let (variant, runnable) = platform_runnable_for(&pc, os).unwrap();
+            assert_eq!(
+                (variant, runnable.commands()),
+                ("default", &["def".to_string()][..])
+            );
+        }
+    }
+}
@@ -0,0 +1,495 @@
+// src/core/templates.rs
+
+use crate::core::paths;
+use crate::output;
+use include_dir::{Dir, include_dir};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Plantillas de proyecto incluidas en el binario (una por subdirectorio de `templates/`).
+pub static TEMPLATES_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/templates");
+
+/// Extensión de los archivos cuyo contenido se procesa (y que se copian sin ella).
+const TEMPLATE_EXTENSION: &str = ".template";
+
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    #[error("Error de Ficheros: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Error de rutas: {0}")]
+    Path(#[from] paths::PathError),
+    #[error("La plantilla '{name}' no existe. Plantillas disponibles: {available}")]
+    NotFound { name: String, available: String },
+    #[error("El archivo '{0}' ya existe; no se sobreescribirá con la plantilla.")]
+    FileExists(PathBuf),
+    #[error(
+        "La plantilla usa '{placeholder}' en '{file}', pero no tiene valor. Pásalo con `--var clave=valor`."
+    )]
+    UnknownPlaceholder { file: PathBuf, placeholder: String },
+    #[error(
+        "La ruta '{rendered}' (de '{file}' en la plantilla) saldría del directorio del proyecto."
+    )]
+    PathOutsideTarget { file: PathBuf, rendered: String },
+    #[error(
+        "No se encontró `git`, necesario para `--template-repo`. Instálalo o usa una plantilla local."
+    )]
+    GitNotAvailable,
+    #[error("{what} '{value}' no es válido: no puede empezar por '-'.")]
+    InvalidGitArgument { what: &'static str, value: String },
+    #[error("`git {command}` falló para el repositorio de plantillas '{url}': {message}")]
+    Git {
+        url: String,
+        command: String,
+        message: String,
+    },
+}
+type TemplateResult<T> = Result<T, TemplateError>;
+
+/// De dónde sale una plantilla.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateOrigin {
+    /// Incluida en el binario.
+    Builtin,
+    /// Un subdirectorio de `~/.config/axes/templates/`.
+    User,
+}
+
+/// Una plantilla ya localizada, lista para `apply_template`.
+#[derive(Debug, Clone)]
+pub enum TemplateSource {
+    Builtin(&'static Dir<'static>),
+    /// Un directorio en disco: una plantilla del usuario o de un repositorio clonado.
+    Directory(PathBuf),
+}
+
+/// Devuelve las plantillas disponibles ordenadas por nombre. Si una plantilla del usuario
+/// se llama igual que una incluida, gana la del usuario.
+pub fn list_templates() -> TemplateResult<Vec<(String, TemplateOrigin)>> {
+    let mut templates: BTreeMap<String, TemplateOrigin> = TEMPLATES_DIR
+        .dirs()
+        .filter_map(|d| d.path().file_name()?.to_str())
+        .map(|name| (name.to_string(), TemplateOrigin::Builtin))
+        .collect();
+
+    let user_dir = paths::get_user_templates_dir()?;
+    if user_dir.is_dir() {
+        for entry in fs::read_dir(&user_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir()
+                && let Some(name) = entry.file_name().to_str()
+            {
+                templates.insert(name.to_string(), TemplateOrigin::User);
+            }
+        }
+    }
+
+    Ok(templates.into_iter().collect())
+}
+
+/// Localiza una plantilla por nombre: primero entre las del usuario y luego entre las incluidas.
+pub fn resolve_template(name: &str) -> TemplateResult<TemplateSource> {
+    let user_template = paths::get_user_templates_dir()?.join(name);
+    if !name.is_empty() && user_template.is_dir() {
+        return Ok(TemplateSource::Directory(user_template));
+    }
+    if let Some(dir) = TEMPLATES_DIR.get_dir(name) {
+        return Ok(TemplateSource::Builtin(dir));
+    }
+
+    let available = list_templates()?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(TemplateError::NotFound {
+        name: name.to_string(),
+        available,
+    })
+}
+
+/// Descarga (o actualiza) un repositorio git de plantillas y devuelve la ruta del clon.
+/// Se hace un clon superficial de `git_ref` (rama o etiqueta; sin ella, la rama por defecto)
+/// en el directorio de caché, uno por URL y referencia. Si el clon ya existe se intenta
+/// actualizar; si eso falla (ej. sin red) se usa el clon guardado con un aviso.
+pub fn fetch_template_repo(url: &str, git_ref: Option<&str>) -> TemplateResult<PathBuf> {
+    // `git` leería un valor que empieza por `-` (ej. `--upload-pack=...`) como una opción.
+    reject_option_like("La URL del repositorio de plantillas", url)?;
+    if let Some(git_ref) = git_ref {
+        reject_option_like("La referencia de la plantilla", git_ref)?;
+    }
+    let clone_dir = paths::get_template_repos_dir()?.join(repo_cache_key(url, git_ref));
+
+    if clone_dir.join(".git").is_dir() {
+        let refspec = git_ref.unwrap_or("HEAD");
+        let updated = run_git(
+            url,
+            &clone_dir,
+            &["fetch", "--depth", "1", "--", "origin", refspec],
+        )
+        .and_then(|_| run_git(url, &clone_dir, &["reset", "--hard", "FETCH_HEAD"]));
+        match updated {
+            Ok(()) => {}
+            Err(TemplateError::GitNotAvailable) => return Err(TemplateError::GitNotAvailable),
+            Err(e) => log::warn!(
+                "No se pudo actualizar el clon de '{}'; se usa el guardado: {}",
+                url,
+                e
+            ),
+        }
+        return Ok(clone_dir);
+    }
+
+    if let Some(parent) = clone_dir.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Un clon a medias (ej. interrumpido) no sirve como caché.
+    if clone_dir.exists() {
+        fs::remove_dir_all(&clone_dir)?;
+    }
+    let clone_path = clone_dir.to_string_lossy().into_owned();
+    let mut args = vec!["clone", "--depth", "1"];
+    if let Some(git_ref) = git_ref {
+        args.extend(["--branch", git_ref]);
+    }
+    args.extend(["--", url, clone_path.as_str()]);
+    if let Err(e) = run_git(url, Path::new("."), &args) {
+        let _ = fs::remove_dir_all(&clone_dir);
+        return Err(e);
+    }
+    Ok(clone_dir)
+}
+
+fn reject_option_like(what: &'static str, value: &str) -> TemplateResult<()> {
+    if value.starts_with('-') {
+        return Err(TemplateError::InvalidGitArgument {
+            what,
+            value: value.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Localiza la plantilla `name` (un subdirectorio) dentro de un repositorio clonado.
+pub fn resolve_repo_template(repo_dir: &Path, name: &str) -> TemplateResult<TemplateSource> {
+    let template_dir = repo_dir.join(name);
+    if !name.is_empty() && name != ".git" && template_dir.is_dir() {
+        return Ok(TemplateSource::Directory(template_dir));
+    }
+
+    let mut available: Vec<String> = fs::read_dir(repo_dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    available.sort();
+    Err(TemplateError::NotFound {
+        name: name.to_string(),
+        available: available.join(", "),
+    })
+}
+
+/// Nombre del directorio de caché de un repositorio: la URL sin esquema, con los caracteres
+/// que no son seguros en una ruta cambiados por `_`, y la referencia si la hay.
+fn repo_cache_key(url: &str, git_ref: Option<&str>) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let sanitize = |text: &str| -> String {
+        text.trim_end_matches('/')
+            .trim_end_matches(".git")
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+    match git_ref {
+        Some(git_ref) => format!("{}@{}", sanitize(without_scheme), sanitize(git_ref)),
+        None => sanitize(without_scheme),
+    }
+}
+
+/// Ejecuta `git` en `cwd` sin heredar la entrada (para que no pida credenciales).
+fn run_git(url: &str, cwd: &Path, args: &[&str]) -> TemplateResult<()> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => TemplateError::GitNotAvailable,
+            _ => TemplateError::Io(e),
+        })?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(TemplateError::Git {
+        url: url.to_string(),
+        command: args.first().copied().unwrap_or_default().to_string(),
+        message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+/// Los valores de `{{name}}`, `{{version}}`, `{{description}}` y `{{year}}` de un proyecto nuevo.
+/// `init` les añade los `--var clave=valor` del usuario.
+pub fn base_context(name: &str, version: &str, description: &str) -> HashMap<String, String> {
+    HashMap::from([
+        ("name".to_string(), name.to_string()),
+        ("version".to_string(), version.to_string()),
+        ("description".to_string(), description.to_string()),
+        ("year".to_string(), current_year().to_string()),
+    ])
+}
+
+/// El año actual (UTC), sin depender de una biblioteca de fechas.
+fn current_year() -> i64 {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+    output::civil_from_days(days).0
+}
+
+/// Copia el árbol de la plantilla en `target`. Los archivos `.template` se copian sin
+/// esa extensión y con cada `{{clave}}` de `context` reemplazado por su valor; el resto se
+/// copia tal cual. Las rutas se sustituyen siempre, sean o no `.template`. Los marcadores
+/// sin valor se dejan intactos o, con `strict`, son un error.
+/// Si algún archivo ya existe (o falla la sustitución) no se escribe nada. Devuelve las
+/// rutas creadas.
+pub fn apply_template(
+    template: &TemplateSource,
+    target: &Path,
+    context: &HashMap<String, String>,
+    strict: bool,
+) -> TemplateResult<Vec<PathBuf>> {
+    // 1. Calcular todos los destinos y comprobar conflictos antes de escribir nada.
+    let files = match template {
+        TemplateSource::Builtin(dir) => {
+            let mut files = Vec::new();
+            collect_builtin_files(dir, dir.path(), &mut files);
+            files
+        }
+        TemplateSource::Directory(dir) => {
+            let mut files = Vec::new();
+            collect_user_files(dir, dir, &mut files)?;
+            files
+        }
+    };
+    let mut planned: Vec<(PathBuf, Vec<u8>)> = Vec::new();
+    for (relative, contents) in files {
+        let is_template = relative.to_string_lossy().ends_with(TEMPLATE_EXTENSION);
+        let contents = match String::from_utf8(contents) {
+            Ok(text) if is_template => {
+                let rendered = render(&text, context);
+                if strict && let Some(placeholder) = find_placeholder(&rendered) {
+                    return Err(TemplateError::UnknownPlaceholder {
+                        file: relative,
+                        placeholder,
+                    });
+                }
+                rendered.into_bytes()
+            }
+            Ok(text) => text.into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+        // Los nombres de archivos y directorios también pueden llevar marcadores
+        // (`src/{{name}}.rs.template`); la extensión `.template` se quita después.
+        let rendered_path = render(&relative.to_string_lossy(), context);
+        if strict && let Some(placeholder) = find_placeholder(&rendered_path) {
+            return Err(TemplateError::UnknownPlaceholder {
+                file: relative,
+                placeholder,
+            });
+        }
+        // Un valor con `..` o una ruta absoluta escribiría fuera del proyecto.
+        let escapes_target = Path::new(&rendered_path)
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+        if escapes_target {
+            return Err(TemplateError::PathOutsideTarget {
+                file: relative,
+                rendered: rendered_path,
+            });
+        }
+        let dest = target.join(strip_template_extension(Path::new(&rendered_path)));
+        planned.push((dest, contents));
+    }
+
+    if let Some((existing, _)) = planned.iter().find(|(dest, _)| dest.exists()) {
+        return Err(TemplateError::FileExists(existing.clone()));
+    }
+
+    // 2. Escribir los archivos.
+    let mut created = Vec::new();
+    for (dest, contents) in planned {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest, contents)?;
+        created.push(dest);
+    }
+
+    Ok(created)
+}
+
+/// Sustituye cada `{{clave}}` de `context`, en orden de clave para que el resultado no
+/// dependa del orden del mapa.
+fn render(text: &str, context: &HashMap<String, String>) -> String {
+    let mut keys: Vec<&String> = context.keys().collect();
+    keys.sort();
+    keys.into_iter().fold(text.to_string(), |acc, key| {
+        acc.replace(&format!("{{{{{}}}}}", key), &context[key])
+    })
+}
+
+/// El primer marcador `{{clave}}` que quede en `text` (clave de letras, dígitos, `_` o `-`).
+fn find_placeholder(text: &str) -> Option<String> {
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        if let Some(end) = after.find("}}") {
+            let key = &after[..end];
+            if !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Some(format!("{{{{{}}}}}", key));
+            }
+        }
+        rest = after;
+    }
+    None
+}
+
+/// Reúne `(ruta relativa a la plantilla, contenido)` de una plantilla incluida.
+fn collect_builtin_files(dir: &Dir, root: &Path, out: &mut Vec<(PathBuf, Vec<u8>)>) {
+    for file in dir.files() {
+        let relative = file.path().strip_prefix(root).unwrap_or(file.path());
+        out.push((relative.to_path_buf(), file.contents().to_vec()));
+    }
+    for sub in dir.dirs() {
+        collect_builtin_files(sub, root, out);
+    }
+}
+
+/// Igual que `collect_builtin_files` para una plantilla en disco.
+fn collect_user_files(
+    dir: &Path,
+    root: &Path,
+    out: &mut Vec<(PathBuf, Vec<u8>)>,
+) -> TemplateResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_user_files(&path, root, out)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            out.push((relative, fs::read(&path)?));
+        }
+    }
+    Ok(())
+}
+
+fn strip_template_extension(path: &Path) -> PathBuf {
+    let as_str = path.to_string_lossy();
+    match as_str.strip_suffix(TEMPLATE_EXTENSION) {
+        Some(stripped) => PathBuf::from(stripped),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_in_directory_and_file_names_are_rendered() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = dir.path().join("plantilla");
+        fs::create_dir_all(template.join("{{name}}")).unwrap();
+        fs::create_dir_all(template.join("src")).unwrap();
+        fs::write(
+            template.join("{{name}}/lib.rs.template"),
+            "// {{name}} v{{version}}\n",
+        )
+        .unwrap();
+        fs::write(
+            template.join("src/{{name}}.rs.template"),
+            "fn {{name}}() {}\n",
+        )
+        .unwrap();
+        // Un archivo que no es `.template` conserva su contenido, pero no su nombre.
+        fs::write(template.join("{{name}}.md"), "{{name}}\n").unwrap();
+
+        let target = dir.path().join("demo");
+        let context = base_context("demo", "0.1.0", "");
+        let created = apply_template(
+            &TemplateSource::Directory(template),
+            &target,
+            &context,
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(created.len(), 3);
+        assert_eq!(
+            fs::read_to_string(target.join("demo/lib.rs")).unwrap(),
+            "// demo v0.1.0\n"
+        );
+        assert_eq!(
+            fs::read_to_string(target.join("src/demo.rs")).unwrap(),
+            "fn demo() {}\n"
+        );
+        assert_eq!(
+            fs::read_to_string(target.join("demo.md")).unwrap(),
+            "{{name}}\n"
+        );
+    }
+
+    #[test]
+    fn option_like_repo_url_or_ref_is_rejected() {
+        assert!(matches!(
+            fetch_template_repo("--upload-pack=touch pwned", None),
+            Err(TemplateError::InvalidGitArgument { .. })
+        ));
+        assert!(matches!(
+            fetch_template_repo("https://example.com/plantillas.git", Some("-b")),
+            Err(TemplateError::InvalidGitArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn rendered_paths_cannot_leave_the_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = dir.path().join("plantilla");
+        fs::create_dir_all(&template).unwrap();
+        fs::write(template.join("{{name}}.txt"), "hola\n").unwrap();
+        let target = dir.path().join("demo");
+
+        for name in ["../fuera", "/tmp/fuera"] {
+            let context = base_context(name, "0.1.0", "");
+            let result = apply_template(
+                &TemplateSource::Directory(template.clone()),
+                &target,
+                &context,
+                false,
+            );
+            assert!(
+                matches!(result, Err(TemplateError::PathOutsideTarget { .. })),
+                "'{}' debería rechazarse",
+                name
+            );
+        }
+        assert!(!dir.path().join("fuera.txt").exists());
+        assert!(!target.exists());
+    }
+}
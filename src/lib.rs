@@ -1,7 +1,16 @@
 // src/lib.rs
 
+pub mod api;
 pub mod cli;
 pub mod constants;
 pub mod core;
 pub mod models;
+pub mod output;
+pub mod prompt;
 pub mod system;
+
+#[cfg(test)]
+mod test_support;
+
+pub use api::{Error, Result, resolve, resolve_from, run_script, run_script_into};
+pub use core::runner::{RunReport, StepPhase, StepReport};
@@ -1,5 +1,6 @@
 // src/models.rs
 
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -16,10 +17,47 @@ pub enum Runnable {
     Single(String),
 }
 
+impl Runnable {
+    /// Devuelve los comandos en orden (uno solo para `Single`).
+    pub fn commands(&self) -> &[String] {
+        match self {
+            Runnable::Sequence(commands) => commands,
+            Runnable::Single(command) => std::slice::from_ref(command),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ExtendedCommand {
     pub run: Runnable,
     pub desc: Option<String>,
+    /// Scripts que deben ejecutarse (una sola vez cada uno) antes de este.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Hook que se ejecuta justo antes de `run`.
+    #[serde(default)]
+    pub pre: Option<Runnable>,
+    /// Hook que se ejecuta justo después de `run`, incluso si este falla.
+    #[serde(default)]
+    pub post: Option<Runnable>,
+    /// Si se define, se pide confirmación (con este texto) antes de ejecutar el script.
+    #[serde(default)]
+    pub confirm: Option<String>,
+    /// En secuencias, ejecuta todos los pasos aunque alguno falle y resume los fallos al final.
+    #[serde(default)]
+    pub continue_on_error: bool,
+    /// Tiempo máximo (en segundos) de cada comando de `run`; al superarlo se mata el proceso.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Reintentos de cada comando de `run` que falle (en secuencias, por paso).
+    #[serde(default)]
+    pub retries: u32,
+    /// Espera (en segundos) entre un intento fallido y el siguiente.
+    #[serde(default)]
+    pub retry_delay_secs: Option<u64>,
+    /// Ejecutar siempre a través de la shell del sistema (por defecto solo si hay operadores).
+    #[serde(default)]
+    pub shell: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -29,6 +67,9 @@ pub struct PlatformCommand {
     pub windows: Option<Runnable>,
     pub linux: Option<Runnable>,
     pub macos: Option<Runnable>,
+    pub freebsd: Option<Runnable>,
+    /// Para cualquier otro SO sin campo propio (ej. `openbsd`), antes de `default`.
+    pub other: Option<Runnable>,
     pub desc: Option<String>,
 }
 
@@ -46,15 +87,60 @@ pub enum Command {
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct OptionsConfig {
     // Campos explícitos para opciones clave
-    pub at_start: Option<String>,
-    pub at_exit: Option<String>,
+    /// Comandos que se ejecutan al iniciar una sesión (un string o una lista).
+    pub at_start: Option<Runnable>,
+    /// Comandos que se ejecutan al salir de una sesión (un string o una lista).
+    pub at_exit: Option<Runnable>,
     pub shell: Option<String>,
+    /// Resolver nombres de proyecto sin distinguir mayúsculas. Solo se lee del proyecto `global`.
+    pub case_insensitive_names: Option<bool>,
+    /// Qué hace `axes <ctx>` sin acción: `"start"` (por defecto) o `"run:<script>"`.
+    pub default_action: Option<String>,
+    /// Comando de `axes <ctx> logs` (ej. `tail -f {root}/var/log/app.log`).
+    pub logs: Option<String>,
+    /// Comando de `axes self-update` (ej. `cargo install axes --force`). Solo se lee del proyecto `global`.
+    pub self_update: Option<String>,
 
     // La sub-tabla `open_with`
     #[serde(default)]
     pub open_with: HashMap<String, String>,
 }
 
+/// La tabla `[inherit]`: qué colecciones hereda un proyecto de sus ancestros.
+/// Con `false`, lo acumulado hasta el padre se descarta y solo queda lo definido en este nivel.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InheritConfig {
+    #[serde(default = "default_true")]
+    pub vars: bool,
+    #[serde(default = "default_true")]
+    pub env: bool,
+    #[serde(default = "default_true")]
+    pub commands: bool,
+    #[serde(default = "default_true")]
+    pub open_with: bool,
+}
+
+impl Default for InheritConfig {
+    fn default() -> Self {
+        Self {
+            vars: true,
+            env: true,
+            commands: true,
+            open_with: true,
+        }
+    }
+}
+
+impl InheritConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
 // --- MODELOS DE `axes.toml` (Lo que se lee del archivo de configuración) ---
 
 /// Representa la estructura deserializada de un archivo `axes.toml`.
@@ -63,14 +149,17 @@ pub struct OptionsConfig {
 pub struct ProjectConfig {
     pub version: Option<String>,
     pub description: Option<String>,
+    /// En el orden en que se definieron en el archivo.
     #[serde(default)]
-    pub commands: HashMap<String, Command>,
+    pub commands: IndexMap<String, Command>,
     #[serde(default)]
     pub options: OptionsConfig,
     #[serde(default)]
     pub vars: HashMap<String, String>,
     #[serde(default)]
     pub env: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "InheritConfig::is_default")]
+    pub inherit: InheritConfig,
 }
 
 impl ProjectConfig {
@@ -80,37 +169,120 @@ impl ProjectConfig {
             "hello".to_string(),
             Command::Simple("echo 'Hello from axes!'".to_string()),
         );
+        // `{path}` es la raíz del proyecto, o el archivo indicado en `axes <ctx> open <app> <ruta>`.
         let mut open_with_defaults = HashMap::new();
+        for (key, command) in [
+            ("vsc", "code '{path}'"),
+            ("idea", "idea '{path}'"),
+            ("subl", "subl '{path}'"),
+            ("zed", "zed '{path}'"),
+        ] {
+            open_with_defaults.insert(key.to_string(), command.to_string());
+        }
         if cfg!(target_os = "windows") {
-            open_with_defaults.insert("explorer".to_string(), "-explorer '{root}'".to_string());
-            open_with_defaults.insert("vsc".to_string(), "code {root}".to_string());
+            open_with_defaults.insert("explorer".to_string(), "-explorer '{path}'".to_string());
             open_with_defaults.insert("default".to_string(), "explorer".to_string());
         } else if cfg!(target_os = "macos") {
-            open_with_defaults.insert("finder".to_string(), "open {root}".to_string());
-            open_with_defaults.insert("vsc".to_string(), "code {root}".to_string());
+            open_with_defaults.insert("finder".to_string(), "open '{path}'".to_string());
             open_with_defaults.insert("default".to_string(), "finder".to_string());
         } else {
             // Linux y otros
-            open_with_defaults.insert("xdg".to_string(), "xdg-open {root}".to_string());
-            open_with_defaults.insert("nautilus".to_string(), "nautilus {root}".to_string());
-            open_with_defaults.insert("vsc".to_string(), "code {root}".to_string());
+            open_with_defaults.insert("xdg".to_string(), "xdg-open '{path}'".to_string());
+            open_with_defaults.insert("nautilus".to_string(), "nautilus '{path}'".to_string());
             open_with_defaults.insert("default".to_string(), "xdg".to_string());
         }
         Self {
             version: Some("0.1.0".to_string()),
             description: Some("A new project managed by axes.".to_string()),
-            commands: HashMap::new(), // Empezar sin comandos por defecto
+            commands: IndexMap::new(), // Empezar sin comandos por defecto
             options: OptionsConfig {
                 open_with: open_with_defaults,
                 at_start: None,
                 at_exit: None,
                 shell: None,
+                case_insensitive_names: None,
+                default_action: None,
+                logs: None,
+                self_update: None,
             },
             ..Default::default()
         }
     }
 }
 
+/// Plantilla del `axes.toml` que crea `axes init`. Los ejemplos van comentados para que el
+/// archivo sirva de referencia sin definir nada; `{{...}}` se sustituye en `default_toml`.
+const DEFAULT_CONFIG_TEMPLATE: &str = r#"# Configuración del proyecto para axes.
+# Descomenta y adapta los ejemplos que necesites.
+
+version = {{version}}
+description = {{description}}
+
+[commands]
+# Comando simple: un único string.
+# hello = "echo 'Hola desde {name}!'"
+
+# Secuencia: se ejecutan en orden y se detiene en el primer fallo.
+# check = ["cargo fmt --check", "cargo clippy"]
+
+# Extendido: con descripción (visible en `axes <ctx> info`), dependencias y hooks.
+# [commands.build]
+# desc = "Compila el proyecto."
+# run = "cargo build --release"
+# depends_on = ["check"]
+# pre = "echo 'Compilando...'"
+
+# Por plataforma: `default` se usa si no hay variante para el sistema actual.
+# [commands.clean]
+# desc = "Borra los artefactos de compilación."
+# windows = "rmdir /s /q target"
+# default = "rm -rf target"
+
+[vars]
+# Variables disponibles como `{nombre}` en los comandos.
+# out_dir = "{root}/dist"
+
+[env]
+# Variables de entorno para los comandos y la sesión.
+# RUST_LOG = "info"
+
+[options]
+# Comandos que se ejecutan al entrar en una sesión con `axes <ctx> start`.
+# at_start = ["git status --short"]
+# Shell de la sesión (ver `shells.toml`).
+# shell = "bash"
+# Qué hace `axes <ctx>` sin acción: "start" (por defecto) o "run:<script>".
+# default_action = "run:dev"
+# Comando de `axes <ctx> logs`.
+# logs = "tail -f {root}/var/log/app.log"
+
+[options.open_with]
+{{open_with}}
+"#;
+
+impl ProjectConfig {
+    /// Genera el contenido del `axes.toml` inicial de un proyecto: los valores de
+    /// `ProjectConfig::new` más ejemplos comentados de cada tipo de comando y opción.
+    pub fn default_toml(description: &str, version: &str) -> String {
+        let quote = |value: &str| toml::Value::String(value.to_string()).to_string();
+
+        let defaults = Self::new();
+        let mut open_with: Vec<_> = defaults.options.open_with.iter().collect();
+        // `default` al final, tras las aplicaciones a las que apunta.
+        open_with.sort_by_key(|(key, _)| (key.as_str() == "default", key.as_str()));
+        let open_with = open_with
+            .into_iter()
+            .map(|(key, command)| format!("{} = {}", key, quote(command)))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        DEFAULT_CONFIG_TEMPLATE
+            .replace("{{version}}", &quote(version))
+            .replace("{{description}}", &quote(description))
+            .replace("{{open_with}}", &open_with)
+    }
+}
+
 // --- MODELOS DE ÍNDICE GLOBAL ---
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
@@ -118,6 +290,10 @@ pub struct IndexEntry {
     pub name: String,
     pub path: PathBuf,
     pub parent: Option<Uuid>,
+    /// Cuándo se registró (segundos Unix). `None` en entradas de índices anteriores.
+    pub created: Option<u64>,
+    /// Último cambio de nombre, padre o ruta (segundos Unix).
+    pub modified: Option<u64>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
@@ -160,7 +336,8 @@ pub struct ResolvedConfig {
     pub project_root: PathBuf,
     pub version: Option<String>,
     pub description: Option<String>,
-    pub commands: HashMap<String, Command>,
+    /// Los comandos de los ancestros primero; un hijo que redefine uno conserva su posición.
+    pub commands: IndexMap<String, Command>,
     pub options: OptionsConfig,
     pub vars: HashMap<String, String>,
     pub env: HashMap<String, String>,
@@ -190,6 +367,22 @@ pub(crate) enum SerializableCommand {
     Platform(PlatformCommand),
 }
 
+/// `IndexEntry` tal como se guardaba antes de `created`/`modified` (índice sin cabecera).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct LegacyIndexEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub parent: Option<Uuid>,
+}
+
+/// `GlobalIndex` con el formato de `LegacyIndexEntry`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct LegacyGlobalIndex {
+    pub projects: HashMap<Uuid, LegacyIndexEntry>,
+    pub aliases: HashMap<String, Uuid>,
+    pub last_used: Option<Uuid>,
+}
+
 /// Un wrapper para `SystemTime` que es serializable.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub(crate) struct SerializableSystemTime(Duration);
@@ -202,15 +395,17 @@ pub(crate) struct SerializableResolvedConfig {
     pub project_root: String,
     pub version: Option<String>,
     pub description: Option<String>,
-    pub commands: HashMap<String, SerializableCommand>,
+    pub commands: IndexMap<String, SerializableCommand>,
     pub options: OptionsConfig,
     pub vars: HashMap<String, String>,
     pub env: HashMap<String, String>,
 }
 
 /// El contenedor principal para el caché de configuración que se escribe en disco.
+/// `schema_version` va primero para poder leerlo antes de decodificar el resto.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct SerializableConfigCache {
+    pub schema_version: u32,
     pub resolved_config: SerializableResolvedConfig,
     pub dependencies: HashMap<String, SerializableSystemTime>,
 }
@@ -240,6 +435,30 @@ impl From<SerializableCommand> for Command {
     }
 }
 
+// LegacyGlobalIndex -> GlobalIndex (las fechas quedan sin valor)
+impl From<LegacyGlobalIndex> for GlobalIndex {
+    fn from(value: LegacyGlobalIndex) -> Self {
+        Self {
+            projects: value
+                .projects
+                .into_iter()
+                .map(|(uuid, entry)| {
+                    let entry = IndexEntry {
+                        name: entry.name,
+                        path: entry.path,
+                        parent: entry.parent,
+                        created: None,
+                        modified: None,
+                    };
+                    (uuid, entry)
+                })
+                .collect(),
+            aliases: value.aliases,
+            last_used: value.last_used,
+        }
+    }
+}
+
 // ResolvedConfig <-> SerializableResolvedConfig
 impl From<&ResolvedConfig> for SerializableResolvedConfig {
     fn from(value: &ResolvedConfig) -> Self {
@@ -293,3 +512,23 @@ impl From<SerializableSystemTime> for SystemTime {
         UNIX_EPOCH + time.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_toml_parses_back_into_a_project_config() {
+        // Las comillas y barras del valor deben escaparse, no romper el TOML.
+        let description = r#"Mi "app" en C:\proyectos"#;
+        let content = ProjectConfig::default_toml(description, "0.3.0");
+
+        let config: ProjectConfig = toml::from_str(&content).unwrap();
+        assert_eq!(config.description.as_deref(), Some(description));
+        assert_eq!(config.version.as_deref(), Some("0.3.0"));
+        assert_eq!(
+            config.options.open_with,
+            ProjectConfig::new().options.open_with
+        );
+    }
+}
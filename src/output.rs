@@ -0,0 +1,92 @@
+// src/output.rs
+
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Si es `true`, se omite la salida decorativa de `axes` (ecos de comandos, banners, notas).
+/// La salida de los comandos hijos y los datos pedidos (ej. `info`, `env`) no se ven afectados.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Nivel de salida de `axes`. Se fija una vez al arrancar, según `--quiet`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputLevel {
+    Normal,
+    Quiet,
+}
+
+pub fn set_level(level: OutputLevel) {
+    QUIET.store(level == OutputLevel::Quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Indica si axes puede usar colores y símbolos no ASCII (`✔`, `✖`, conectores del árbol).
+/// Se desactiva con `NO_COLOR` (ver https://no-color.org) o `AXES_NO_COLOR`, si tienen valor.
+pub fn use_color() -> bool {
+    static USE_COLOR: OnceLock<bool> = OnceLock::new();
+    *USE_COLOR.get_or_init(|| {
+        let is_set = |var| std::env::var_os(var).is_some_and(|value| !value.is_empty());
+        !is_set("NO_COLOR") && !is_set("AXES_NO_COLOR")
+    })
+}
+
+/// El tema de los diálogos interactivos: con colores o, sin ellos, el tema plano.
+pub fn theme() -> Box<dyn Theme> {
+    if use_color() {
+        Box::new(ColorfulTheme::default())
+    } else {
+        Box::new(SimpleTheme)
+    }
+}
+
+/// Marca de los mensajes de éxito.
+pub fn ok_mark() -> &'static str {
+    if use_color() { "✔" } else { "[OK]" }
+}
+
+/// Marca de los mensajes de fallo.
+pub fn fail_mark() -> &'static str {
+    if use_color() { "✖" } else { "[ERROR]" }
+}
+
+/// Formatea una fecha en segundos Unix como `AAAA-MM-DD HH:MM UTC`, sin depender de una
+/// biblioteca de fechas.
+pub fn format_timestamp(unix_secs: u64) -> String {
+    let (year, month, day) = civil_from_days((unix_secs / 86_400) as i64);
+    let secs_of_day = unix_secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60
+    )
+}
+
+/// Convierte días desde 1970-01-01 en `(año, mes, día)` del calendario gregoriano.
+/// Algoritmo `civil_from_days` de Howard Hinnant.
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Como `println!`, pero para mensajes decorativos que `--quiet` debe silenciar.
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() {
+            println!($($arg)*);
+        }
+    };
+}
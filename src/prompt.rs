@@ -0,0 +1,74 @@
+// src/prompt.rs
+
+use crate::output;
+use crate::status;
+use dialoguer::{Confirm, Error as DialoguerError};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PromptError {
+    #[error(
+        "Se necesita una respuesta interactiva ({0}), pero axes se ejecuta en modo no interactivo. \
+         Pasa el dato por argumentos o usa `--yes`/`--no` para las confirmaciones."
+    )]
+    NonInteractive(String),
+    #[error("Error de Interfaz de Usuario: {0}")]
+    Dialoguer(#[from] DialoguerError),
+}
+
+/// Si es `false`, cualquier pregunta falla en lugar de quedarse esperando una respuesta.
+/// En los tests nunca se pregunta: uno que llegue a una pregunta falla en lugar de esperar.
+static INTERACTIVE: AtomicBool = AtomicBool::new(!cfg!(test));
+
+/// Respuesta fija para las confirmaciones: 0 = preguntar, 1 = sí (`--yes`), 2 = no (`--no`).
+static CONFIRM_ANSWER: AtomicU8 = AtomicU8::new(0);
+
+/// Fija el modo de interacción. Se llama una vez al arrancar, según `--non-interactive`,
+/// `--yes` y `--no`. Sin una terminal en la entrada estándar el modo es siempre no interactivo.
+pub fn configure(non_interactive: bool, confirm_answer: Option<bool>) {
+    let interactive = !non_interactive && std::io::stdin().is_terminal();
+    INTERACTIVE.store(interactive, Ordering::Relaxed);
+    let answer = match confirm_answer {
+        None => 0,
+        Some(true) => 1,
+        Some(false) => 2,
+    };
+    CONFIRM_ANSWER.store(answer, Ordering::Relaxed);
+}
+
+pub fn is_interactive() -> bool {
+    INTERACTIVE.load(Ordering::Relaxed)
+}
+
+/// Devuelve un error descriptivo si no se puede preguntar al usuario.
+/// `what` describe el dato que se iba a pedir (ej. "selección del proyecto padre").
+pub fn require_interactive(what: &str) -> Result<(), PromptError> {
+    if is_interactive() {
+        Ok(())
+    } else {
+        Err(PromptError::NonInteractive(what.to_string()))
+    }
+}
+
+/// Pide una confirmación sí/no. Con `--yes`/`--no` se responde sola (también en una
+/// terminal); sin ellos, en modo no interactivo falla en lugar de bloquearse.
+pub fn confirm(prompt: &str, default: bool) -> Result<bool, PromptError> {
+    match CONFIRM_ANSWER.load(Ordering::Relaxed) {
+        1 => {
+            status!("{} sí (--yes)", prompt);
+            return Ok(true);
+        }
+        2 => {
+            status!("{} no (--no)", prompt);
+            return Ok(false);
+        }
+        _ => {}
+    }
+    require_interactive(&format!("confirmación '{}'", prompt))?;
+    Ok(Confirm::with_theme(&*output::theme())
+        .with_prompt(prompt)
+        .default(default)
+        .interact()?)
+}
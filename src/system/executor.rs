@@ -4,7 +4,9 @@ use dunce;
 use std::collections::HashMap;
 use std::io::ErrorKind; // Necesario para la detección de errores
 use std::path::Path;
-use std::process::{Command as StdCommand, Stdio};
+use std::process::{Child, Command as StdCommand, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -15,8 +17,100 @@ pub enum ExecutionError {
     EmptyCommand,
     #[error("El comando '{0}' no se pudo ejecutar: {1}")]
     CommandFailed(String, std::io::Error),
-    #[error("El comando '{0}' finalizó con un código de error no nulo.")]
-    NonZeroExitStatus(String),
+    #[error("El comando '{command}' finalizó con un código de error no nulo{}.", format_exit_code(.code))]
+    NonZeroExitStatus { command: String, code: Option<i32> },
+    #[error("El comando '{command}' superó el tiempo límite de {secs} segundos y fue terminado.")]
+    Timeout { command: String, secs: u64 },
+    #[error("El comando '{0}' fue interrumpido por el usuario.")]
+    Interrupted(String),
+}
+
+impl ExecutionError {
+    /// Código de salida del proceso hijo, si terminó con uno (no por una señal).
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            ExecutionError::NonZeroExitStatus { code, .. } => *code,
+            // Mismo código que usa la utilidad `timeout` de coreutils.
+            ExecutionError::Timeout { .. } => Some(124),
+            // Código estándar para Ctrl+C (128 + SIGINT).
+            ExecutionError::Interrupted(_) => Some(130),
+            _ => None,
+        }
+    }
+}
+
+fn format_exit_code(code: &Option<i32>) -> String {
+    code.map(|c| format!(" ({})", c)).unwrap_or_default()
+}
+
+/// Opciones adicionales para `execute_command_with_options`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ExecOptions {
+    /// Si se supera, se mata el proceso (y sus descendientes).
+    pub timeout: Option<Duration>,
+    /// Ejecutar siempre a través de la shell del sistema, aunque no haya operadores.
+    pub force_shell: bool,
+}
+
+/// Operadores que solo una shell POSIX sabe interpretar.
+const SHELL_OPERATORS: &[&str] = &["&&", "||", "|", ";", ">", "<", "$", "`", "&", "*"];
+
+/// Operadores que solo `cmd.exe` sabe interpretar.
+const WINDOWS_SHELL_OPERATORS: &[&str] = &["&&", "||", "|", "&", ">", "<", "%"];
+
+/// Builtins POSIX habituales que no existen como ejecutables.
+const POSIX_SHELL_BUILTINS: &[&str] = &[
+    "cd", "export", "unset", "source", ".", "alias", "set", "exit", "eval", "exec", "ulimit",
+    "umask", "trap", "wait",
+];
+
+/// Se activa desde el manejador de Ctrl+C. Mientras esté activo, el hijo en curso se detiene
+/// y no se lanzan comandos nuevos.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Con `run --json`, la salida estándar de los comandos va a stderr, para que stdout solo
+/// contenga el resumen JSON.
+static STDOUT_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Cada cuánto se comprueba el estado del hijo mientras se espera.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Tiempo que se da al hijo para terminar tras reenviarle Ctrl+C antes de matarlo.
+const INTERRUPT_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// Notifica al ejecutor que el usuario pulsó Ctrl+C. Se llama desde el manejador de señales.
+pub fn notify_interrupted() {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Indica si se ha recibido una interrupción (Ctrl+C).
+pub fn was_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Envía la salida estándar de los comandos que se ejecuten a partir de ahora a stderr.
+pub fn redirect_stdout_to_stderr(enabled: bool) {
+    STDOUT_TO_STDERR.store(enabled, Ordering::Relaxed);
+}
+
+/// La salida estándar para un hijo: la de axes o, si se redirigió, su stderr.
+fn child_stdout() -> Stdio {
+    if STDOUT_TO_STDERR.load(Ordering::Relaxed) {
+        std::io::stderr().into()
+    } else {
+        Stdio::inherit()
+    }
+}
+
+/// Las variables de entorno ordenadas por nombre, para inyectarlas (y registrarlas) siempre
+/// en el mismo orden aunque vengan de un `HashMap`.
+pub fn sorted_env_vars(env_vars: &HashMap<String, String>) -> Vec<(&str, &str)> {
+    let mut sorted: Vec<(&str, &str)> = env_vars
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+    sorted.sort_unstable_by_key(|(key, _)| *key);
+    sorted
 }
 
 /// Ejecuta un comando de sistema de forma robusta y predecible.
@@ -25,6 +119,20 @@ pub fn execute_command(
     cwd: &Path,
     env_vars: &HashMap<String, String>,
 ) -> Result<(), ExecutionError> {
+    execute_command_with_options(command_line, cwd, env_vars, ExecOptions::default())
+}
+
+/// Igual que `execute_command`, con tiempo límite y ejecución forzada a través de la shell.
+pub fn execute_command_with_options(
+    command_line: &str,
+    cwd: &Path,
+    env_vars: &HashMap<String, String>,
+    options: ExecOptions,
+) -> Result<(), ExecutionError> {
+    let timeout = options.timeout;
+    if was_interrupted() {
+        return Err(ExecutionError::Interrupted(command_line.to_string()));
+    }
     let trimmed_command = command_line.trim();
     if trimmed_command.is_empty() {
         return Err(ExecutionError::EmptyCommand);
@@ -55,21 +163,31 @@ pub fn execute_command(
 
     // --- El Enfoque Unificado ---
 
-    // 1. Intentar la ejecución directa
-    let mut command = StdCommand::new(program);
+    // 1. Intentar la ejecución directa (o vía la shell del sistema si el comando la necesita)
+    let mut command = if options.force_shell || requires_shell(final_command_line, program) {
+        system_shell_command(final_command_line)
+    } else {
+        let mut direct_command = StdCommand::new(program);
+        direct_command.args(args);
+        direct_command
+    };
     command
-        .args(args)
         .current_dir(clean_cwd)
-        .envs(env_vars)
-        .stdout(Stdio::inherit())
+        .envs(sorted_env_vars(env_vars))
+        .stdout(child_stdout())
         .stderr(Stdio::inherit());
+    let isolated = isolate_process_group(&mut command, timeout);
 
-    match command.status() {
-        Ok(status) => {
+    match command.spawn() {
+        Ok(child) => {
+            let status = wait_for_child(child, command_line, timeout, isolated, &was_interrupted)?;
             // El programa se encontró y se ejecutó.
             if !status.success() {
                 if !ignore_errors {
-                    return Err(ExecutionError::NonZeroExitStatus(command_line.to_string()));
+                    return Err(ExecutionError::NonZeroExitStatus {
+                        command: command_line.to_string(),
+                        code: status.code(),
+                    });
                 } else {
                     log::warn!(
                         "El comando finalizó con un código de error no nulo, pero fue ignorado como se solicitó."
@@ -86,21 +204,30 @@ pub fn execute_command(
                     program
                 );
 
-                let mut fallback_command = StdCommand::new("cmd");
+                // Pasamos la línea completa para que `cmd` la parsee.
+                let mut fallback_command = system_shell_command(final_command_line);
                 fallback_command
-                    .arg("/C")
-                    .arg(command_line) // Pasamos la línea completa para que `cmd` la parsee.
                     .current_dir(dunce::simplified(cwd))
-                    .envs(env_vars)
-                    .stdout(Stdio::inherit())
+                    .envs(sorted_env_vars(env_vars))
+                    .stdout(child_stdout())
                     .stderr(Stdio::inherit());
 
-                let fallback_status = fallback_command
-                    .status()
+                let fallback_child = fallback_command
+                    .spawn()
                     .map_err(|e| ExecutionError::CommandFailed(command_line.to_string(), e))?;
+                let fallback_status = wait_for_child(
+                    fallback_child,
+                    command_line,
+                    timeout,
+                    false,
+                    &was_interrupted,
+                )?;
 
                 if !fallback_status.success() {
-                    return Err(ExecutionError::NonZeroExitStatus(command_line.to_string()));
+                    return Err(ExecutionError::NonZeroExitStatus {
+                        command: command_line.to_string(),
+                        code: fallback_status.code(),
+                    });
                 }
             } else {
                 // Si el error es otro (ej. permisos) o no estamos en Windows, es un error real.
@@ -112,4 +239,211 @@ pub fn execute_command(
     Ok(())
 }
 
-// La función `is_windows_shell_builtin` ya no es necesaria y ha sido eliminada.
+/// Indica si el comando usa operadores de shell o (en Unix) empieza por un builtin POSIX.
+/// Los comandos simples se siguen ejecutando directamente, que es más rápido.
+/// En Windows, los builtins de `cmd` se cubren con el reintento tras `NotFound`.
+fn requires_shell(command_line: &str, program: &str) -> bool {
+    if cfg!(windows) {
+        WINDOWS_SHELL_OPERATORS
+            .iter()
+            .any(|op| command_line.contains(op))
+    } else {
+        POSIX_SHELL_BUILTINS.contains(&program)
+            || SHELL_OPERATORS.iter().any(|op| command_line.contains(op))
+    }
+}
+
+/// Construye un comando que delega la línea completa, sin tokenizar, en la shell del sistema:
+/// `cmd /C` en Windows y `sh -c` en el resto.
+fn system_shell_command(command_line: &str) -> StdCommand {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        log::debug!("Ejecutando '{}' a través de `cmd /C`.", command_line);
+        let mut shell_command = StdCommand::new("cmd");
+        // `raw_arg` evita que Rust re-escape las comillas que `cmd` debe interpretar.
+        shell_command.arg("/C").raw_arg(command_line);
+        shell_command
+    }
+    #[cfg(not(windows))]
+    {
+        log::debug!("Ejecutando '{}' a través de `sh -c`.", command_line);
+        let mut shell_command = StdCommand::new("sh");
+        shell_command.arg("-c").arg(command_line);
+        shell_command
+    }
+}
+
+/// Con tiempo límite, en Unix el hijo se lanza en su propio grupo de procesos para poder
+/// matar también a sus descendientes. Sin tiempo límite se mantiene en el grupo de `axes`
+/// para que siga recibiendo Ctrl+C desde la terminal. Tampoco se aísla si la entrada es una
+/// terminal: un grupo en segundo plano se detendría (SIGTTIN) al leer de ella, así que un
+/// comando interactivo (una pregunta, un editor) se quedaría parado. Devuelve si se aisló.
+fn isolate_process_group(command: &mut StdCommand, timeout: Option<Duration>) -> bool {
+    #[cfg(unix)]
+    if timeout.is_some() && !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+        return true;
+    }
+    let _ = (command, timeout);
+    false
+}
+
+/// Espera a que el hijo termine. Si se supera `timeout`, lo mata (con `isolated`, junto a sus
+/// descendientes); si `interrupted` lo indica (normalmente `was_interrupted`, activado por Ctrl+C), le
+/// reenvía la interrupción y, si no termina en `INTERRUPT_GRACE_PERIOD`, lo mata.
+fn wait_for_child(
+    mut child: Child,
+    command_line: &str,
+    timeout: Option<Duration>,
+    isolated: bool,
+    interrupted: &dyn Fn() -> bool,
+) -> Result<ExitStatus, ExecutionError> {
+    let to_failed = |e| ExecutionError::CommandFailed(command_line.to_string(), e);
+
+    let deadline = timeout.map(|t| Instant::now() + t);
+    loop {
+        if let Some(status) = child.try_wait().map_err(to_failed)? {
+            return Ok(status);
+        }
+        if interrupted() {
+            log::info!(
+                "Interrupción recibida; deteniendo el proceso {} ('{}').",
+                child.id(),
+                command_line
+            );
+            stop_interrupted_child(&mut child, isolated);
+            return Err(ExecutionError::Interrupted(command_line.to_string()));
+        }
+        if let (Some(deadline), Some(timeout)) = (deadline, timeout)
+            && Instant::now() >= deadline
+        {
+            log::warn!(
+                "El comando '{}' superó el tiempo límite; terminando el proceso {}.",
+                command_line,
+                child.id()
+            );
+            kill_process_tree(&mut child);
+            // Recoger el proceso para no dejar zombis.
+            let _ = child.wait();
+            return Err(ExecutionError::Timeout {
+                command: command_line.to_string(),
+                secs: timeout.as_secs(),
+            });
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Reenvía la interrupción al hijo y, si no termina a tiempo, lo mata.
+/// `isolated` indica que el hijo tiene su propio grupo de procesos (ver `isolate_process_group`)
+/// y por tanto no recibió el Ctrl+C de la terminal.
+fn stop_interrupted_child(child: &mut Child, isolated: bool) {
+    #[cfg(unix)]
+    {
+        let pid = child.id() as libc::pid_t;
+        let target = if isolated { -pid } else { pid };
+        unsafe {
+            libc::kill(target, libc::SIGINT);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = isolated;
+
+    let grace_deadline = Instant::now() + INTERRUPT_GRACE_PERIOD;
+    let mut exited = false;
+    while Instant::now() < grace_deadline {
+        if let Ok(Some(_)) = child.try_wait() {
+            exited = true;
+            break;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    // En un grupo aislado pueden quedar descendientes (ej. procesos en segundo plano que
+    // ignoran SIGINT) aunque el hijo ya haya terminado: se mata el grupo completo.
+    if !exited || isolated {
+        kill_process_tree(child);
+    }
+    let _ = child.wait();
+}
+
+/// Mata el proceso hijo y todos sus descendientes.
+fn kill_process_tree(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        // Si el hijo es líder de su grupo (ver `isolate_process_group`), un PID negativo
+        // envía la señal a todo el grupo; si no lo es, falla y se mata solo al hijo.
+        let pgid = child.id() as libc::pid_t;
+        if unsafe { libc::kill(-pgid, libc::SIGKILL) } != 0 {
+            let _ = child.kill();
+        }
+    }
+    #[cfg(windows)]
+    {
+        // `/T` termina también los procesos hijos, `/F` fuerza la terminación.
+        let killed_tree = StdCommand::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success());
+        if !killed_tree {
+            let _ = child.kill();
+        }
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = child.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(command_line: &str) -> Result<(), ExecutionError> {
+        let dir = tempfile::tempdir().unwrap();
+        execute_command(command_line, dir.path(), &HashMap::new())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn piped_command_runs_through_the_shell() {
+        assert!(requires_shell("echo hola | grep -q hola", "echo"));
+        assert!(run("echo hola | grep -q hola").is_ok());
+        assert!(matches!(
+            run("echo hola | grep -q adios"),
+            Err(ExecutionError::NonZeroExitStatus { code: Some(1), .. })
+        ));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn piped_command_runs_through_the_shell() {
+        assert!(requires_shell("echo hola | findstr hola", "echo"));
+        assert!(run("echo hola | findstr hola").is_ok());
+        assert!(matches!(
+            run("echo hola | findstr adios"),
+            Err(ExecutionError::NonZeroExitStatus { code: Some(1), .. })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn interrupt_stops_the_running_child() {
+        let child = StdCommand::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id() as libc::pid_t;
+        let started = Instant::now();
+        // Simula un Ctrl+C a los 200 ms sin tocar el indicador global, que compartirían
+        // el resto de tests.
+        let interrupted = || started.elapsed() >= Duration::from_millis(200);
+
+        let result = wait_for_child(child, "sleep 30", None, false, &interrupted);
+
+        assert!(matches!(result, Err(ExecutionError::Interrupted(_))));
+        assert!(started.elapsed() < Duration::from_secs(10));
+        // El proceso ya fue recogido: no queda nadie con ese PID.
+        assert_ne!(unsafe { libc::kill(pid, 0) }, 0);
+    }
+}
@@ -0,0 +1,112 @@
+// src/system/logging.rs
+
+use env_logger::{Builder, Logger, Target, WriteStyle};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::OpenOptions;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LoggingError {
+    #[error("No se pudo abrir el archivo de log '{path}': {source}")]
+    LogFile {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("El logger ya estaba inicializado: {0}")]
+    AlreadyInitialized(#[from] log::SetLoggerError),
+}
+
+/// Nivel mínimo que se escribe en el archivo de `--log-file`, para que siempre sea útil
+/// al diagnosticar un problema aunque la terminal esté en silencio.
+const LOG_FILE_MIN_LEVEL: LevelFilter = LevelFilter::Debug;
+
+/// Envía cada registro a la terminal y, opcionalmente, a un archivo, cada uno con su filtro.
+struct TeeLogger {
+    stderr: Logger,
+    file: Option<Logger>,
+}
+
+impl Log for TeeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.stderr.enabled(metadata) || self.file.as_ref().is_some_and(|f| f.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        if self.stderr.matches(record) {
+            self.stderr.log(record);
+        }
+        if let Some(file) = &self.file
+            && file.matches(record)
+        {
+            file.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+        if let Some(file) = &self.file {
+            file.flush();
+        }
+    }
+}
+
+/// Convierte el número de `-v` en un nivel: 1 = info, 2 = debug, 3 o más = trace.
+fn verbosity_to_level(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::Error,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Construye un builder que respeta `RUST_LOG`, salvo que se pidan `-v` explícitamente.
+fn base_builder(verbosity: u8) -> Builder {
+    if verbosity > 0 {
+        let mut builder = Builder::new();
+        builder.filter_level(verbosity_to_level(verbosity));
+        builder
+    } else {
+        Builder::from_default_env()
+    }
+}
+
+/// Inicializa el logging global. Sin `log_file` se comporta como `env_logger::init()`.
+/// Con `log_file`, además se añaden los registros (al menos de nivel debug) a ese archivo.
+pub fn init(verbosity: u8, log_file: Option<&Path>) -> Result<(), LoggingError> {
+    let mut stderr = base_builder(verbosity);
+    if !crate::output::use_color() {
+        stderr.write_style(WriteStyle::Never);
+    }
+    let stderr = stderr.build();
+
+    let file = match log_file {
+        Some(path) => {
+            let handle = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|source| LoggingError::LogFile {
+                    path: path.display().to_string(),
+                    source,
+                })?;
+            let mut builder = base_builder(verbosity);
+            if stderr.filter() < LOG_FILE_MIN_LEVEL {
+                builder.filter_level(LOG_FILE_MIN_LEVEL);
+            }
+            builder
+                .target(Target::Pipe(Box::new(handle)))
+                .write_style(WriteStyle::Never);
+            Some(builder.build())
+        }
+        None => None,
+    };
+
+    let max_level = file
+        .as_ref()
+        .map_or(stderr.filter(), |f| f.filter().max(stderr.filter()));
+    log::set_boxed_logger(Box::new(TeeLogger { stderr, file }))?;
+    log::set_max_level(max_level);
+    Ok(())
+}
@@ -1,4 +1,6 @@
 // src/system/mod.rs
 
 pub mod executor;
+pub mod logging;
 pub mod shell;
+pub mod watcher;
@@ -1,5 +1,6 @@
 // src/system/shell.rs
 
+use crate::core::interpolator;
 use crate::models::{ResolvedConfig, ShellConfig, ShellsConfig};
 use crate::system::executor;
 use std::collections::HashMap;
@@ -17,8 +18,14 @@ pub enum ShellError {
     TempFile(#[from] tempfile::PersistError),
     #[error("No se pudo encontrar el directorio de configuración de axes.")]
     ConfigDirNotFound,
-    #[error("La shell solicitada '{0}' no está definida en shells.toml.")]
-    ShellNotDefined(String),
+    #[error(
+        "La shell solicitada '{name}' no está definida en '{path}'. Shells disponibles: {available}"
+    )]
+    ShellNotDefined {
+        name: String,
+        path: String,
+        available: String,
+    },
     #[error("No se pudo determinar una shell por defecto para este sistema operativo.")]
     NoDefaultShell,
     #[error("Error al parsear shells.toml: {0}")]
@@ -39,19 +46,25 @@ pub fn launch_interactive_shell(config: &ResolvedConfig) -> Result<(), ShellErro
             .to_string(),
     };
 
-    let shell_config = shells_config
-        .shells
-        .get(&shell_name)
-        .ok_or_else(|| ShellError::ShellNotDefined(shell_name.clone()))?;
+    let shell_config = shells_config.shells.get(&shell_name).ok_or_else(|| {
+        let mut available: Vec<&str> = shells_config.shells.keys().map(String::as_str).collect();
+        available.sort();
+        ShellError::ShellNotDefined {
+            name: shell_name.clone(),
+            path: shells_config_path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| "shells.toml".to_string()),
+            available: available.join(", "),
+        }
+    })?;
 
-    // 2. Crear el script de inicialización temporal
-    let is_windows_shell = shell_name == "cmd" || shell_name == "powershell";
-    let script_extension = if is_windows_shell { ".bat" } else { ".sh" };
+    // 2. Crear el script de inicialización temporal, con la sintaxis de la shell elegida
+    let shell_kind = ShellKind::detect(&shell_name, shell_config);
     let temp_script_file = NamedTempFile::with_prefix("axes-init-")?
         .into_temp_path()
-        .with_extension(script_extension);
+        .with_extension(shell_kind.script_extension());
 
-    let script_content = build_init_script(config, is_windows_shell);
+    let script_content = build_init_script(config, shell_kind);
 
     fs::write(&temp_script_file, script_content)?;
 
@@ -91,24 +104,22 @@ pub fn launch_interactive_shell(config: &ResolvedConfig) -> Result<(), ShellErro
     }
 
     // 5. **NUEVA LÓGICA**: Ejecutar el hook `at_exit`
-    if let Some(at_exit_command) = &config.options.at_exit
-        && !at_exit_command.trim().is_empty()
-    {
+    if let Some(at_exit) = &config.options.at_exit {
         //println!("\nEjecutando hook 'at_exit'...");
 
         // Usamos nuestro ejecutor de comandos estándar.
         // No pasamos parámetros, pero sí el entorno del proyecto.
-        let interpolator = crate::core::interpolator::Interpolator::new(config, &[]);
-        let final_command = interpolator.interpolate(at_exit_command);
-
-        if let Err(e) = executor::execute_command(&final_command, &config.project_root, &config.env)
-        {
-            // Si `at_exit` falla, no queremos que toda la operación de `axes` falle.
-            // Es una operación de limpieza, por lo que solo mostramos una advertencia.
-            eprintln!(
-                "\nAdvertencia: El hook 'at_exit' falló al ejecutarse: {}",
-                e
-            );
+        for final_command in interpolator::option_commands(config, at_exit) {
+            if let Err(e) =
+                executor::execute_command(&final_command, &config.project_root, &config.env)
+            {
+                // Si `at_exit` falla, no queremos que toda la operación de `axes` falle.
+                // Es una operación de limpieza, así que avisamos y seguimos con el resto.
+                eprintln!(
+                    "\nAdvertencia: El hook 'at_exit' falló al ejecutarse: {}",
+                    e
+                );
+            }
         }
     }
 
@@ -117,35 +128,130 @@ pub fn launch_interactive_shell(config: &ResolvedConfig) -> Result<(), ShellErro
     Ok(())
 }
 
-/// Construye el contenido del script de inicialización.
-fn build_init_script(config: &ResolvedConfig, is_windows: bool) -> String {
-    let mut script = String::new();
+/// Sintaxis de la shell de la sesión. Decide cómo se escribe el script de inicialización
+/// (extensión, variables, encadenado de comandos) que la shell carga con sus
+/// `interactive_args` (`--rcfile`, `/K`, `-File`...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShellKind {
+    Cmd,
+    PowerShell,
+    Fish,
+    /// bash, zsh, sh y cualquier shell desconocida (se asume sintaxis POSIX).
+    Posix,
+}
 
-    // Silenciar comandos
-    if is_windows {
-        script.push_str("@echo off\n");
-    } else {
-        // Podríamos usar `set +v` o simplemente no poner nada para shells POSIX
+impl ShellKind {
+    /// Detecta la sintaxis por el nombre de la shell o, si no es conocido, por su ejecutable.
+    fn detect(shell_name: &str, shell_config: &ShellConfig) -> Self {
+        let executable = shell_config
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        [shell_name.to_lowercase(), executable]
+            .iter()
+            .find_map(|name| match name.as_str() {
+                "cmd" => Some(ShellKind::Cmd),
+                "powershell" | "pwsh" => Some(ShellKind::PowerShell),
+                "fish" => Some(ShellKind::Fish),
+                "bash" | "zsh" | "sh" => Some(ShellKind::Posix),
+                _ => None,
+            })
+            .unwrap_or(ShellKind::Posix)
     }
 
-    // Añadir variables de [env]
-    for (key, value) in &config.env {
-        if is_windows {
-            script.push_str(&format!("set \"{}={}\"\n", key, value));
-        } else {
-            script.push_str(&format!("export {}='{}'\n", key, value));
+    fn script_extension(self) -> &'static str {
+        match self {
+            ShellKind::Cmd => "bat",
+            ShellKind::PowerShell => "ps1",
+            ShellKind::Fish => "fish",
+            ShellKind::Posix => "sh",
+        }
+    }
+
+    /// Líneas que van al principio del script (ej. silenciar el eco en `cmd`).
+    fn preamble(self) -> &'static str {
+        match self {
+            ShellKind::Cmd => "@echo off\n",
+            _ => "",
         }
     }
 
-    // Añadir hook at_start
-    if let Some(at_start) = &config.options.at_start
-        && !at_start.trim().is_empty()
-    {
-        if is_windows {
-            script.push_str(&format!("call {}\n", at_start));
-        } else {
-            // `source` es más robusto que `.`
-            script.push_str(&format!("source \"{}\" || . \"{}\"\n", at_start, at_start));
+    fn set_env(self, key: &str, value: &str) -> String {
+        match self {
+            ShellKind::Cmd => format!("set \"{}={}\"", key, value),
+            ShellKind::PowerShell => format!("$env:{} = {}", key, powershell_quote(value)),
+            ShellKind::Fish => format!("set -gx {} {}", key, posix_quote(value)),
+            ShellKind::Posix => format!("export {}={}", key, posix_quote(value)),
+        }
+    }
+
+    /// Encadena comandos de forma que cada uno solo se ejecute si el anterior tuvo éxito.
+    fn chain(self, commands: &[String]) -> String {
+        match self {
+            // `call` permite que un `.bat` (ej. `activate.bat`) devuelva el control al script.
+            ShellKind::Cmd => commands
+                .iter()
+                .map(|c| format!("call {}", c))
+                .collect::<Vec<_>>()
+                .join(" && "),
+            // Windows PowerShell 5 no tiene `&&`; `$?` funciona en todas las versiones.
+            ShellKind::PowerShell => {
+                let mut chained = String::new();
+                for (i, command) in commands.iter().enumerate() {
+                    if i == 0 {
+                        chained.push_str(command);
+                    } else {
+                        chained.push_str(&format!("; if ($?) {{ {}", command));
+                    }
+                }
+                chained.push_str(&" }".repeat(commands.len().saturating_sub(1)));
+                chained
+            }
+            ShellKind::Fish => commands.join("; and "),
+            ShellKind::Posix => commands.join(" && "),
+        }
+    }
+
+    /// Imprime una línea en blanco seguida de `message`.
+    fn echo(self, message: &str) -> String {
+        match self {
+            ShellKind::Cmd => format!("echo.\necho {}", message),
+            ShellKind::PowerShell => {
+                format!("Write-Host ''\nWrite-Host {}", powershell_quote(message))
+            }
+            ShellKind::Fish | ShellKind::Posix => format!("echo ''\necho {}", posix_quote(message)),
+        }
+    }
+}
+
+/// Entrecomilla un valor con comillas simples para shells POSIX (y fish).
+fn posix_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Entrecomilla un valor con comillas simples para PowerShell.
+fn powershell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Construye el contenido del script de inicialización.
+fn build_init_script(config: &ResolvedConfig, kind: ShellKind) -> String {
+    let mut script = String::from(kind.preamble());
+
+    // Añadir variables de [env]
+    for (key, value) in executor::sorted_env_vars(&config.env) {
+        script.push_str(&kind.set_env(key, value));
+        script.push('\n');
+    }
+
+    // Añadir hook at_start: los comandos se ejecutan dentro de la propia shell de la sesión,
+    // así que `source ./.venv/bin/activate` o `export X=1` afectan a la sesión.
+    if let Some(at_start) = &config.options.at_start {
+        let commands = interpolator::option_commands(config, at_start);
+        if !commands.is_empty() {
+            script.push_str(&kind.chain(&commands));
+            script.push('\n');
         }
     }
 
@@ -154,21 +260,24 @@ fn build_init_script(config: &ResolvedConfig, is_windows: bool) -> String {
         "--- Sesión de axes para '{}' iniciada. Escribe 'exit' para salir. ---",
         config.qualified_name
     );
-    if is_windows {
-        script.push_str(&format!("\necho.\necho {}\n", welcome_message));
-    } else {
-        script.push_str(&format!("\necho ''\necho '{}'\n", welcome_message));
-    }
+    script.push('\n');
+    script.push_str(&kind.echo(&welcome_message));
+    script.push('\n');
 
     script
 }
 
+/// Ruta de `shells.toml` en el directorio de configuración global de axes.
+fn shells_config_path() -> Result<PathBuf, ShellError> {
+    let config_dir =
+        crate::core::paths::get_axes_config_dir().map_err(|_| ShellError::ConfigDirNotFound)?;
+    Ok(config_dir.join("shells.toml"))
+}
+
 /// Carga la configuración de shells desde el disco.
 /// Si el archivo no existe, lo genera con valores por defecto y lo guarda.
 fn load_shells_config() -> Result<ShellsConfig, ShellError> {
-    let config_dir =
-        crate::core::paths::get_axes_config_dir().map_err(|_| ShellError::ConfigDirNotFound)?;
-    let shells_path = config_dir.join("shells.toml");
+    let shells_path = shells_config_path()?;
 
     if !shells_path.exists() {
         log::warn!("'shells.toml' no encontrado. Generando archivo de configuración por defecto.");
@@ -0,0 +1,125 @@
+// src/system/watcher.rs
+
+use crate::constants::AXES_DIR;
+use crate::system::executor;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum WatchError {
+    #[error("Error del observador de archivos: {0}")]
+    Notify(#[from] notify::Error),
+    #[error("Patrón de --ignore inválido: {0}")]
+    InvalidPattern(#[from] glob::PatternError),
+    #[error("La ruta a observar '{0}' no existe.")]
+    PathNotFound(PathBuf),
+}
+
+/// Tiempo sin nuevos cambios que se espera antes de re-ejecutar, para agrupar ráfagas
+/// (ej. un `git checkout` o un editor que guarda varios archivos).
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Cada cuánto se comprueba si el usuario pulsó Ctrl+C mientras se esperan cambios.
+const INTERRUPT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Directorios cuyos cambios nunca disparan una re-ejecución.
+const ALWAYS_IGNORED_DIRS: &[&str] = &[".git"];
+
+/// Extensión de los cachés que axes escribe en `.axes/` al resolver el proyecto: si no se
+/// ignoraran, cada ejecución provocaría la siguiente.
+const AXES_CACHE_EXTENSION: &str = "bin";
+
+/// Qué observar en `watch`.
+#[derive(Debug, Clone, Default)]
+pub struct WatchOptions {
+    /// Rutas observadas recursivamente.
+    pub paths: Vec<PathBuf>,
+    /// Patrones glob (relativos a la raíz del proyecto) cuyos cambios se ignoran.
+    pub ignore: Vec<String>,
+}
+
+/// Ejecuta `on_change` una vez y de nuevo tras cada tanda de cambios en `options.paths`,
+/// hasta que el usuario pulsa Ctrl+C. `root` es la raíz contra la que se evalúan los
+/// patrones de `options.ignore`.
+pub fn watch(
+    root: &Path,
+    options: &WatchOptions,
+    mut on_change: impl FnMut(),
+) -> Result<(), WatchError> {
+    let ignore = options
+        .ignore
+        .iter()
+        .map(|p| glob::Pattern::new(p))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        // Si el receptor ya no existe, el bucle terminó: no hay nada que hacer.
+        let _ = tx.send(res);
+    })?;
+    for path in &options.paths {
+        if !path.exists() {
+            return Err(WatchError::PathNotFound(path.clone()));
+        }
+        watcher.watch(path, RecursiveMode::Recursive)?;
+    }
+
+    on_change();
+
+    loop {
+        // 1. Esperar al primer cambio relevante.
+        match rx.recv_timeout(INTERRUPT_POLL_INTERVAL) {
+            Ok(Ok(event)) if is_relevant(&event, root, &ignore) => {}
+            Ok(Ok(_)) => continue,
+            Ok(Err(e)) => {
+                log::warn!("Error al observar archivos: {}", e);
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if executor::was_interrupted() {
+                    return Ok(());
+                }
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+
+        // 2. Agrupar los cambios que lleguen seguidos.
+        while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+        if executor::was_interrupted() {
+            return Ok(());
+        }
+        on_change();
+    }
+}
+
+/// Un evento cuenta si modifica algo y al menos una de sus rutas no está ignorada.
+fn is_relevant(event: &Event, root: &Path, ignore: &[glob::Pattern]) -> bool {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+    event
+        .paths
+        .iter()
+        .any(|path| !is_ignored(path, root, ignore))
+}
+
+fn is_ignored(path: &Path, root: &Path, ignore: &[glob::Pattern]) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+
+    let in_ignored_dir = relative.components().any(|c| {
+        ALWAYS_IGNORED_DIRS
+            .iter()
+            .any(|dir| c.as_os_str() == std::ffi::OsStr::new(dir))
+    });
+    let is_axes_cache = relative.parent().is_some_and(|p| p.ends_with(AXES_DIR))
+        && relative
+            .extension()
+            .is_some_and(|ext| ext == AXES_CACHE_EXTENSION);
+
+    in_ignored_dir || is_axes_cache || ignore.iter().any(|pattern| pattern.matches_path(relative))
+}
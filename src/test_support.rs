@@ -0,0 +1,91 @@
+// src/test_support.rs
+
+//! Utilidades para los tests: índices globales de prueba cuyos proyectos viven en un
+//! directorio temporal, sin tocar la configuración real del usuario.
+
+use crate::constants::{AXES_DIR, PROJECT_CONFIG_FILENAME};
+use crate::core::index_manager::GLOBAL_PROJECT_UUID;
+use crate::models::{GlobalIndex, IndexEntry};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+/// El directorio de configuración de axes durante los tests (ver `paths::get_axes_config_dir`):
+/// uno temporal, creado una sola vez por proceso y compartido por todos los tests.
+pub(crate) fn config_dir() -> &'static Path {
+    static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
+    CONFIG_DIR.get_or_init(|| tempfile::tempdir().unwrap().keep())
+}
+
+/// Escribe `content` como el `axes.toml` del proyecto en `root`.
+pub(crate) fn write_config(root: &Path, content: &str) {
+    let axes_dir = root.join(AXES_DIR);
+    fs::create_dir_all(&axes_dir).unwrap();
+    fs::write(axes_dir.join(PROJECT_CONFIG_FILENAME), content).unwrap();
+}
+
+/// Un índice global con el proyecto `global` y los proyectos que se le añadan, cada uno
+/// con su directorio y su `axes.toml` dentro de un directorio temporal.
+pub(crate) struct TestIndex {
+    pub index: GlobalIndex,
+    /// Directorio temporal (canonicalizado) que contiene todos los proyectos.
+    pub root: PathBuf,
+    _dir: TempDir,
+}
+
+impl TestIndex {
+    pub fn new() -> Self {
+        Self::with_global_config("")
+    }
+
+    /// Como `new`, con `global_config` como `axes.toml` del proyecto `global`.
+    pub fn with_global_config(global_config: &str) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        let mut test_index = Self {
+            index: GlobalIndex::default(),
+            root,
+            _dir: dir,
+        };
+        let global_path = test_index.root.join("global");
+        write_config(&global_path, global_config);
+        test_index
+            .index
+            .projects
+            .insert(GLOBAL_PROJECT_UUID, entry("global", global_path, None));
+        test_index
+    }
+
+    /// Registra `name` como hijo de `parent`, en `<root>/<rel_path>`, con un `axes.toml` vacío.
+    pub fn add(&mut self, name: &str, parent: Uuid, rel_path: &str) -> Uuid {
+        self.add_with_config(name, parent, rel_path, "")
+    }
+
+    pub fn add_with_config(
+        &mut self,
+        name: &str,
+        parent: Uuid,
+        rel_path: &str,
+        config: &str,
+    ) -> Uuid {
+        let path = self.root.join(rel_path);
+        write_config(&path, config);
+        let uuid = Uuid::new_v4();
+        self.index
+            .projects
+            .insert(uuid, entry(name, path, Some(parent)));
+        uuid
+    }
+}
+
+fn entry(name: &str, path: PathBuf, parent: Option<Uuid>) -> IndexEntry {
+    IndexEntry {
+        name: name.to_string(),
+        path,
+        parent,
+        created: None,
+        modified: None,
+    }
+}